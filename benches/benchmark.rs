@@ -2,7 +2,8 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 use pijersi_rs::game::Game;
 use pijersi_rs::logic::perft::perft;
-use pijersi_rs::search::alphabeta::{search_node, BASE_ALPHA, BASE_BETA};
+use pijersi_rs::search::alphabeta::{search_node, SearchContext, BASE_ALPHA, BASE_BETA};
+use pijersi_rs::search::eval::{EvalWeights, StaticEval};
 
 fn bench_perft(c: &mut Criterion) {
     let mut game = Game::new();
@@ -25,16 +26,18 @@ fn bench_perft(c: &mut Criterion) {
 fn bench_evaluate_action(c: &mut Criterion) {
     let mut board = Game::new();
     board.init();
+    let context = SearchContext::new(None, None, &EvalWeights::NONE);
+    let static_eval = StaticEval::new(&board.board, &context);
     c.bench_function("search_node 1", |b| {
         b.iter(|| {
             black_box(search_node(
                 (&board.board, 1 - board.current_player),
                 1,
+                Default::default(),
                 (BASE_ALPHA, BASE_BETA),
-                None,
                 Default::default(),
-                None,
-                0,
+                static_eval,
+                &context,
             ))
         })
     });
@@ -43,11 +46,11 @@ fn bench_evaluate_action(c: &mut Criterion) {
             black_box(search_node(
                 (&board.board, 1 - board.current_player),
                 2,
+                Default::default(),
                 (BASE_ALPHA, BASE_BETA),
-                None,
                 Default::default(),
-                None,
-                0,
+                static_eval,
+                &context,
             ))
         })
     });
@@ -56,11 +59,11 @@ fn bench_evaluate_action(c: &mut Criterion) {
             black_box(search_node(
                 (&board.board, 1 - board.current_player),
                 3,
+                Default::default(),
                 (BASE_ALPHA, BASE_BETA),
-                None,
                 Default::default(),
-                None,
-                0,
+                static_eval,
+                &context,
             ))
         })
     });
@@ -69,11 +72,11 @@ fn bench_evaluate_action(c: &mut Criterion) {
             black_box(search_node(
                 (&board.board, 1 - board.current_player),
                 4,
+                Default::default(),
                 (BASE_ALPHA, BASE_BETA),
-                None,
                 Default::default(),
-                None,
-                0,
+                static_eval,
+                &context,
             ))
         })
     });
@@ -82,7 +85,6 @@ fn bench_evaluate_action(c: &mut Criterion) {
 fn bench_search(c: &mut Criterion) {
     let mut board = Game::new();
     board.init();
-    board.options.verbose = false;
     c.bench_function("search 1", |b| {
         b.iter(|| black_box(board.search_to_depth(1, None, None)))
     });
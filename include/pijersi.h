@@ -0,0 +1,84 @@
+/*
+ * C ABI of the Natural Selection Pijersi engine (see src/ffi.rs).
+ *
+ * Link against the pijersi_rs dynamic library built by `cargo build --release`.
+ * Strings are NUL-terminated UTF-8, output strings are written to buffers allocated by the caller.
+ *
+ * No panic of the engine unwinds into the caller: the functions that return an error code return PIJERSI_PANIC,
+ * pijersi_engine_new returns null and the other functions return false or -1.
+ */
+
+#ifndef PIJERSI_H
+#define PIJERSI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Error codes returned by the functions that can fail. */
+typedef enum PijersiErrorCode {
+    PIJERSI_OK = 0,
+    PIJERSI_NULL_POINTER = 1,
+    PIJERSI_INVALID_STRING = 2,
+    PIJERSI_INVALID_ARGUMENT = 3,
+    PIJERSI_BUFFER_TOO_SMALL = 4,
+    PIJERSI_RULES = 5,
+    PIJERSI_PARSE = 6,
+    PIJERSI_UGI = 7,
+    PIJERSI_NETWORK = 8,
+    PIJERSI_DATA = 9,
+    PIJERSI_NO_ACTION = 10,
+    PIJERSI_PANIC = 11,
+} PijersiErrorCode;
+
+/* Opaque engine handle. */
+typedef struct PijersiEngine PijersiEngine;
+
+/* Creates a new engine at the starting position (destroy it with pijersi_engine_free), returns null on failure. */
+PijersiEngine *pijersi_engine_new(void);
+
+/* Destroys an engine (does nothing if the handle is null). */
+void pijersi_engine_free(PijersiEngine *handle);
+
+/* Sets the position from a Pijersi Standard Notation string (positions that cannot occur in a game are rejected). */
+PijersiErrorCode pijersi_engine_set_position(PijersiEngine *handle, const char *psn);
+
+/* Writes the Pijersi Standard Notation string of the position to the buffer. */
+PijersiErrorCode pijersi_engine_get_position(const PijersiEngine *handle, char *buffer, size_t size);
+
+/* Plays an action given as a string ("a1b1c1" or "a1b1" format). */
+PijersiErrorCode pijersi_engine_play(PijersiEngine *handle, const char *action);
+
+/*
+ * Writes the legal actions of the current player to the buffer, separated by spaces.
+ * The buffer size needed (including the NUL terminator) is written to `required_size` (can be null), also when
+ * PIJERSI_BUFFER_TOO_SMALL is returned: the call can be repeated with a buffer of that size.
+ */
+PijersiErrorCode pijersi_engine_legal_actions(const PijersiEngine *handle, char *buffer, size_t size,
+                                              size_t *required_size);
+
+/* Returns whether a player has won. */
+bool pijersi_engine_is_win(const PijersiEngine *handle);
+
+/* Returns whether the game is drawn. */
+bool pijersi_engine_is_draw(const PijersiEngine *handle);
+
+/* Returns the winner (0 or 1) or -1 if there is none. */
+int32_t pijersi_engine_winner(const PijersiEngine *handle);
+
+/*
+ * Searches the best action and writes it to the buffer (7 bytes are enough) and its score to `score` (can be null).
+ * The search is limited by depth and/or time in milliseconds, 0 means no limit (at least one limit is required).
+ */
+PijersiErrorCode pijersi_engine_search(const PijersiEngine *handle, uint64_t depth, uint64_t movetime,
+                                       char *buffer, size_t size, int16_t *score);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PIJERSI_H */
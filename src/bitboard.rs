@@ -2,8 +2,14 @@
 
 use std::ops::{BitAnd, BitOr, Index, IndexMut, Not};
 
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use crate::{
-    logic::{index::CellIndex, Player},
+    logic::{
+        index::{CellIndex, CellIndexTrait},
+        Player, N_CELLS,
+    },
     piece::{
         Piece, PieceTrait, BLACK_PAPER, BLACK_ROCK, BLACK_SCISSORS, BLACK_WISE, HALF_PIECE_WIDTH,
         WHITE_PAPER, WHITE_ROCK, WHITE_SCISSORS, WHITE_WISE,
@@ -38,9 +44,58 @@ pub struct Bitboard(pub u64);
 /// | 13    | Bottom   | Black | Paper    |
 /// | 14    | Bottom   | Black | Rock     |
 /// | 15    | Bottom   | Black | Wise     |
+///
+/// It is serialized as its Pijersi Standard Notation board string in human-readable formats and as its raw bitboards otherwise.
+/// The raw bitboards are rejected if they do not describe at most one top piece and one bottom piece per cell, with no bottom piece alone.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Board(pub [Bitboard; N_BITBOARDS]);
 
+/// Mask of the 45 bits used by a bitboard
+const CELLS_MASK: u64 = (1 << 45) - 1;
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_fen())
+        } else {
+            self.0.map(|bitboard| bitboard.0).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let board_string = String::deserialize(deserializer)?;
+            Self::try_from_fen(&board_string).map_err(D::Error::custom)
+        } else {
+            let bitboards = <[u64; N_BITBOARDS]>::deserialize(deserializer)?;
+            if bitboards.iter().any(|bitboard| bitboard & !CELLS_MASK != 0) {
+                return Err(D::Error::custom("bitboard with bits outside of the board"));
+            }
+            let (top, bottom) = bitboards.split_at(N_BITBOARDS / 2);
+            let (Some(top), Some(bottom)) = (layer_union(top), layer_union(bottom)) else {
+                return Err(D::Error::custom(
+                    "cell with several pieces in the same layer",
+                ));
+            };
+            if bottom & !top != 0 {
+                return Err(D::Error::custom(
+                    "cell with a bottom piece and no top piece",
+                ));
+            }
+            Ok(Self(bitboards.map(Bitboard)))
+        }
+    }
+}
+
+/// Returns the union of the bitboards of a layer (top or bottom pieces), or None if two of them overlap.
+fn layer_union(layer: &[u64]) -> Option<u64> {
+    layer.iter().try_fold(0, |union, &bitboard| {
+        (union & bitboard == 0).then_some(union | bitboard)
+    })
+}
+
 impl Iterator for Bitboard {
     type Item = usize;
 
@@ -87,6 +142,20 @@ impl Bitboard {
         let mask = 1 << index;
         self.0 ^= mask;
     }
+
+    /// Returns the bitboard mirrored left-right.
+    pub fn mirror(self) -> Self {
+        let mut mirrored = Self::EMPTY;
+        for index in self {
+            mirrored.set(index.mirror());
+        }
+        mirrored
+    }
+
+    /// Returns the bitboard rotated by 180°.
+    pub fn rotate(self) -> Self {
+        Self(self.0.reverse_bits() >> (64 - N_CELLS))
+    }
 }
 
 impl Not for Bitboard {
@@ -275,6 +344,21 @@ impl Board {
         self.unset_piece(index, piece);
     }
 
+    /// Returns the board mirrored left-right.
+    ///
+    /// The rules are symmetric: the actions of the mirrored board are the mirrored actions (see [`crate::logic::actions::ActionTrait::mirror`]).
+    pub fn mirror(&self) -> Self {
+        Self(self.0.map(Bitboard::mirror))
+    }
+
+    /// Returns the board rotated by 180° with the colours of the pieces swapped.
+    ///
+    /// The rules are symmetric: the actions of the other player on the flipped board are the flipped actions (see [`crate::logic::actions::ActionTrait::flip_colours`]).
+    pub fn flip_colours(&self) -> Self {
+        // Swapping the colour bit of the bitboard index swaps white and black pieces
+        Self(std::array::from_fn(|k| self[k ^ 4].rotate()))
+    }
+
     /// Initializes the the board to the starting configuration.
     ///
     /// Sets the pieces to their original position.
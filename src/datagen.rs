@@ -0,0 +1,240 @@
+//! This module implements a self-play training data generator.
+//!
+//! Games are played by the engine against itself from randomized openings: a number of random legal actions are played from the starting position, then each side searches at a fixed depth (and optionally a fixed number of nodes).
+//! Every searched position is recorded with the search score and best action, and is labelled with the final result of the game once it ends.
+//!
+//! The searches use the options of the engine (evaluation weights, network, search features) and its transposition table, which is shared by all the games.
+//! The searches stopped by the node limit do not write to the transposition table, so the games do not read each other's unfinished scores.
+//! A game is discarded if one of its searches does not find an action, since its result would be unknown.
+//! Games are played in parallel using the rayon thread pool.
+//!
+//! The positions can be written in two formats:
+//! * A compact binary format (a bincode-encoded `Vec<TrainingPosition>`), see [`write_binary`]
+//! * A plain text format with one position per line (`[psn];[score];[action];[result]`), see [`write_text`]
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::RwLock;
+
+use bincode::{Decode, Encode};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::bitboard::Board;
+use crate::errors::DataErrorKind;
+use crate::game::{Game, GameOptions, SearchLimits};
+use crate::hash::search::SearchTable;
+use crate::logic::actions::Action;
+use crate::logic::translate::action_to_string;
+use crate::logic::{Player, N_CELLS};
+use crate::piece::{Piece, PieceTrait};
+use crate::search::Score;
+
+/// The parameters of the training data generation.
+#[derive(Debug, Clone, Copy)]
+pub struct DatagenOptions {
+    /// The number of games to play
+    pub games: u64,
+    /// The search depth used to choose every action
+    pub depth: u64,
+    /// The maximum number of nodes searched to choose every action (optional)
+    pub nodes: Option<u64>,
+    /// The number of random actions played at the start of each game
+    pub random_plies: u64,
+    /// The seed of the random openings (game `i` uses the seed `seed + i`)
+    pub seed: u64,
+}
+
+impl Default for DatagenOptions {
+    fn default() -> Self {
+        Self {
+            games: 100,
+            depth: 3,
+            nodes: None,
+            random_plies: 8,
+            seed: 0,
+        }
+    }
+}
+
+/// A position recorded during self-play.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TrainingPosition {
+    /// The pieces on each cell (see [`crate::piece`])
+    pub cells: [Piece; N_CELLS],
+    /// The side to move: 0 if white, 1 if black
+    pub player: Player,
+    /// The half moves counter
+    pub half_moves: u64,
+    /// The full moves counter
+    pub full_moves: u64,
+    /// The search score from the point of view of the side to move
+    pub score: Score,
+    /// The best action found by the search
+    pub action: Action,
+    /// The final result of the game from the point of view of white: 1 if white won, -1 if black won, 0 if drawn
+    pub result: i8,
+}
+
+impl TrainingPosition {
+    /// Returns the board of the recorded position.
+    pub fn board(&self) -> Board {
+        let mut board = Board::EMPTY;
+        for (index, &piece) in self.cells.iter().enumerate() {
+            if !piece.is_empty() {
+                board.set_piece(index, piece);
+            }
+        }
+        board
+    }
+
+    /// Returns the Pijersi Standard Notation of the recorded position.
+    pub fn to_psn(&self) -> String {
+        let mut game = Game::new();
+        game.set_state(&self.board(), self.player, self.half_moves, self.full_moves);
+        game.get_string_state()
+    }
+
+    /// Converts the position to a line of the plain text format: `[psn];[score];[action];[result]`.
+    pub fn to_text(&self) -> String {
+        format!(
+            "{};{};{};{}",
+            self.to_psn(),
+            self.score,
+            action_to_string(&self.board(), self.action),
+            self.result
+        )
+    }
+}
+
+/// Returns the final result of a finished game from the point of view of white.
+fn game_result(game: &Game) -> i8 {
+    let winner = if game.board.is_win() {
+        game.get_winner()
+    } else if game.board.is_stalemate(game.current_player) {
+        // The player who cannot move loses
+        Some(1 - game.current_player)
+    } else {
+        None
+    };
+    match winner {
+        Some(0) => 1,
+        Some(1) => -1,
+        _ => 0,
+    }
+}
+
+/// Plays a self-play game and returns its recorded positions.
+///
+/// The random opening is determined by the seed. Positions from the random opening are not recorded.
+/// No positions are returned if a search does not find an action before the game ends (for instance if the node limit is too low to complete the first iteration).
+///
+/// The actions are searched with the given game options and transposition table (if there is one).
+/// The stopped searches do not write to the transposition table, so it can be shared by games played in parallel.
+pub fn play_game(
+    options: &DatagenOptions,
+    seed: u64,
+    game_options: &GameOptions,
+    transposition_table: Option<&RwLock<SearchTable>>,
+) -> Vec<TrainingPosition> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut game = Game::new();
+    game.options = game_options.clone();
+    game.init();
+
+    for _ in 0..options.random_plies {
+        if game.is_win() || game.is_draw() {
+            break;
+        }
+        let actions = game.board.available_player_actions(game.current_player);
+        let action = actions[rng.random_range(0..actions.len())];
+        game.play(action).unwrap();
+    }
+
+    let limits = SearchLimits {
+        depth: Some(options.depth),
+        movetime: None,
+        nodes: options.nodes,
+    };
+    let mut positions = vec![];
+    while !game.is_win() && !game.is_draw() {
+        let Some((action, score)) = game.search(&limits, None, transposition_table, None) else {
+            // The game cannot be finished so its result is unknown, its positions are discarded
+            return vec![];
+        };
+        let (board, player, half_moves, full_moves) = game.get_state();
+        positions.push(TrainingPosition {
+            cells: std::array::from_fn(|index| board.get_piece(index)),
+            player,
+            half_moves,
+            full_moves,
+            score,
+            action,
+            result: 0,
+        });
+        game.play(action).unwrap();
+    }
+
+    let result = game_result(&game);
+    for position in &mut positions {
+        position.result = result;
+    }
+    positions
+}
+
+/// Plays the self-play games in parallel and returns all the recorded positions, ordered by game.
+///
+/// The actions are searched with the given game options and transposition table (if there is one).
+pub fn generate(
+    options: &DatagenOptions,
+    game_options: &GameOptions,
+    transposition_table: Option<&RwLock<SearchTable>>,
+) -> Vec<TrainingPosition> {
+    (0..options.games)
+        .into_par_iter()
+        .map(|game_index| {
+            play_game(
+                options,
+                options.seed.wrapping_add(game_index),
+                game_options,
+                transposition_table,
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// Writes the positions to a file in the binary format.
+pub fn write_binary<P: AsRef<Path>>(
+    path: P,
+    positions: &[TrainingPosition],
+) -> Result<(), DataErrorKind> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    bincode::encode_into_std_write(positions, &mut writer, bincode::config::standard())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads positions from a file in the binary format.
+pub fn read_binary<P: AsRef<Path>>(path: P) -> Result<Vec<TrainingPosition>, DataErrorKind> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(bincode::decode_from_std_read(
+        &mut reader,
+        bincode::config::standard(),
+    )?)
+}
+
+/// Writes the positions to a file in the plain text format.
+pub fn write_text<P: AsRef<Path>>(
+    path: P,
+    positions: &[TrainingPosition],
+) -> Result<(), DataErrorKind> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for position in positions {
+        writeln!(writer, "{}", position.to_text())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! This module implements the `Engine` facade used to embed the engine in other programs.
+//!
+//! The engine owns a game, the opening book, the transposition table and the thread pool the searches run in.
+//! It never prints anything: the progress of the searches is reported as typed [`SearchInfo`] events to an optional callback.
+//!
+//! ```ignore
+//! let mut engine = Engine::new();
+//! engine.load();
+//! engine.game.play_from_string("a5b5d4")?;
+//! let (sender, receiver) = std::sync::mpsc::channel();
+//! let result = engine.search(&SearchLimits::depth(4), Some(&|info| sender.send(info).unwrap()));
+//! ```
+//!
+//! [`SearchInfo`]: crate::search::info::SearchInfo
+
+use std::sync::{Arc, RwLock};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use crate::datagen::{generate, DatagenOptions, TrainingPosition};
+use crate::game::{Game, SearchLimits};
+use crate::hash::search::SearchTable;
+use crate::search::eval::EvalTerm;
+use crate::search::info::InfoCallback;
+#[cfg(feature = "nnue")]
+use crate::search::nnue::Network;
+use crate::search::openings::OpeningBook;
+use crate::search::suite::{run_suite, SuiteEntry, SuiteLimit, SuiteResult};
+use crate::search::{Score, SearchResult};
+
+/// The default number of threads of the thread pool
+pub const DEFAULT_THREADS: usize = 8;
+
+/// The engine facade: a game with the resources used to search it.
+pub struct Engine {
+    /// The current game and its options
+    pub game: Game,
+    opening_book: Option<OpeningBook>,
+    transposition_table: Option<RwLock<SearchTable>>,
+    thread_pool: Arc<ThreadPool>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    /// Creates a new `Engine` with the starting position and a thread pool of [`DEFAULT_THREADS`] threads.
+    ///
+    /// The opening book and the transposition table are not available until [`Engine::load`] is called.
+    pub fn new() -> Self {
+        let mut game = Game::new();
+        game.init();
+        Self {
+            game,
+            opening_book: None,
+            transposition_table: None,
+            thread_pool: Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(DEFAULT_THREADS)
+                    .build()
+                    .expect("Failed to build the thread pool"),
+            ),
+        }
+    }
+
+    /// Loads the opening book and allocates a new transposition table (expensive).
+    pub fn load(&mut self) {
+        self.opening_book = Some(OpeningBook::new());
+        self.transposition_table = Some(RwLock::new(SearchTable::default()));
+    }
+
+    /// Resets the game to the starting position.
+    pub fn new_game(&mut self) {
+        self.game.init();
+    }
+
+    /// Returns the thread pool the searches run in.
+    pub fn thread_pool(&self) -> Arc<ThreadPool> {
+        Arc::clone(&self.thread_pool)
+    }
+
+    /// Replaces the thread pool by a new one with the chosen number of threads.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), ThreadPoolBuildError> {
+        self.thread_pool = Arc::new(ThreadPoolBuilder::new().num_threads(threads).build()?);
+        Ok(())
+    }
+
+    /// Sets the weight of an evaluation term.
+    ///
+    /// The transposition table is emptied if the weight changes since its scores were computed with the previous weight.
+    pub fn set_eval_weight(&mut self, term: EvalTerm, weight: Score) {
+        if self.game.options.eval_weights.get(term) != weight {
+            self.game.options.eval_weights.set(term, weight);
+            self.clear_transposition_table();
+        }
+    }
+
+    /// Sets the network used by the evaluation (none to use the piece-square tables and the evaluation terms).
+    ///
+    /// The transposition table is emptied since its scores were computed with the previous evaluation.
+    #[cfg(feature = "nnue")]
+    pub fn set_network(&mut self, network: Option<Network>) {
+        self.game.options.network = network;
+        self.clear_transposition_table();
+    }
+
+    /// Empties the transposition table (if it has been allocated).
+    pub fn clear_transposition_table(&self) {
+        if let Some(transposition_table) = &self.transposition_table {
+            transposition_table.write().unwrap().empty();
+        }
+    }
+
+    /// Returns the transposition table (if it has been allocated).
+    pub fn transposition_table(&self) -> Option<&RwLock<SearchTable>> {
+        self.transposition_table.as_ref()
+    }
+
+    /// Searches the current position within the given limits in the thread pool and returns the best action and its score.
+    ///
+    /// The information events of the search are reported to the callback (if there is one).
+    pub fn search(
+        &self,
+        limits: &SearchLimits,
+        info: Option<&InfoCallback>,
+    ) -> Option<SearchResult> {
+        self.thread_pool
+            .install(|| {
+                self.game.search(
+                    limits,
+                    self.opening_book.as_ref(),
+                    self.transposition_table.as_ref(),
+                    info,
+                )
+            })
+            .map(|(action, score)| SearchResult {
+                action: action.into(),
+                score,
+            })
+    }
+
+    /// Generates training data in the thread pool with the options of the game and the transposition table (see [`generate`]).
+    pub fn generate_data(&self, options: &DatagenOptions) -> Vec<TrainingPosition> {
+        self.thread_pool.install(|| {
+            generate(
+                options,
+                &self.game.options,
+                self.transposition_table.as_ref(),
+            )
+        })
+    }
+
+    /// Runs a test suite in the thread pool (see [`run_suite`]).
+    pub fn run_suite(&self, entries: &[SuiteEntry], limit: SuiteLimit) -> Vec<SuiteResult> {
+        self.thread_pool.install(|| {
+            run_suite(
+                &self.game,
+                entries,
+                limit,
+                self.transposition_table.as_ref(),
+            )
+        })
+    }
+}
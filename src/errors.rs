@@ -1,13 +1,16 @@
 //! This module contains custom errors for this crate.
 
-use std::{fmt::Display, num::ParseIntError};
+use std::{fmt::Display, io, num::ParseIntError};
 
 use thiserror::Error;
 
 use crate::logic::{
     actions::{Action, ActionTrait},
+    index::CellIndex,
+    translate::{index_to_string, piece_to_char},
     Player,
 };
+use crate::piece::Piece;
 
 /// General Pijersi errors
 #[derive(Debug, Error)]
@@ -21,6 +24,12 @@ pub enum RuntimeError {
     /// UGI engine error
     #[error("UGI error at {}:{}:{}.", file!(), line!(), column!())]
     UGI(#[from] UgiErrorKind),
+    /// Failed loading a network
+    #[error("Network error at {}:{}:{}.", file!(), line!(), column!())]
+    Network(#[from] NetworkErrorKind),
+    /// Failed reading or writing training data
+    #[error("Training data error at {}:{}:{}.", file!(), line!(), column!())]
+    Data(#[from] DataErrorKind),
 }
 
 /// Errors returned if game rules are broken
@@ -48,6 +57,9 @@ pub enum ParseErrorKind {
     /// Invalid action
     #[error("Invalid action string. Expected \"a1b1c1\" or \"a1b1\" format.")]
     InvalidAction,
+    /// Invalid move
+    #[error("Invalid move string. Expected \"a1b1c1\" format.")]
+    InvalidMove,
     /// Invalid position
     #[error("Invalid position string. See documentation at https://github.com/eclypse-prime/pijersi-rs/blob/main/UGI.md.")]
     InvalidPosition(#[from] InvalidPositionKind),
@@ -71,10 +83,19 @@ pub enum ParseErrorKind {
     /// Invalid int
     #[error("Invalid int string.")]
     InvalidInt(#[from] ParseIntError),
+    /// Invalid evaluation term
+    #[error("Invalid evaluation term. Expected \"mobility\", \"threats\", \"unprotected\", \"advancement\" or \"wise-blocking\".")]
+    InvalidEvalTerm,
+    /// Invalid test suite line
+    #[error("Invalid test suite line. Expected \"[psn] ; [operation] ; ...\" with operations \"bm [moves]\", \"am [moves]\", \"score [min] [max]\" or \"id [name]\".")]
+    InvalidSuiteLine,
+    /// Invalid search mode
+    #[error("Invalid search mode. Expected \"split\" or \"lazy-smp\".")]
+    InvalidSearchMode,
 }
 
 /// The different kinds of invalid position errors
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum InvalidPositionKind {
     /// Wrong number of lines
     #[error("Invalid number of lines in board notation: {0} (expected 7)")]
@@ -82,6 +103,40 @@ pub enum InvalidPositionKind {
     /// Wrong number of cells
     #[error("Invalid number of characters in board notation: {0} (expected 45*2 = 90)")]
     WrongCharNumber(usize),
+    /// Wrong number of cells in a line
+    #[error(
+        "Invalid number of cells in line {line} of board notation: {count} (expected {expected})"
+    )]
+    WrongCellNumber {
+        /// The line (starting from 0 for the top line)
+        line: usize,
+        /// The number of cells in the line
+        count: usize,
+        /// The expected number of cells (6 or 7)
+        expected: usize,
+    },
+    /// Invalid character
+    #[error("Invalid character in board notation: '{0}'")]
+    InvalidCharacter(char),
+    /// Stack made of pieces of different colours
+    #[error("The stack on {} has pieces of different colours", index_to_string(*.0))]
+    MixedStack(CellIndex),
+    /// Wise on top of another piece type
+    #[error("The stack on {} has a wise on top of another piece type", index_to_string(*.0))]
+    WiseOnNonWise(CellIndex),
+    /// More pieces of one kind than the set contains
+    #[error("Too many '{}' pieces: {count} (the set contains {max})", piece_to_char(*.piece).unwrap_or('?'))]
+    TooManyPieces {
+        /// The piece (colour and type)
+        piece: Piece,
+        /// The number of pieces of this kind on the board
+        count: usize,
+        /// The number of pieces of this kind in the set
+        max: usize,
+    },
+    /// A player has already won
+    #[error("Player {0} has already won (a piece other than a wise is on the last row)")]
+    AlreadyWon(Player),
 }
 
 /// The kind of coordinates error (vertical or horizontal)
@@ -113,6 +168,37 @@ impl Display for InvalidCoordinatesKind {
     }
 }
 
+/// Errors returned if a network weights file cannot be loaded
+#[derive(Debug, Error)]
+pub enum NetworkErrorKind {
+    /// The file could not be read
+    #[error("Could not read the network file.")]
+    Io(#[from] io::Error),
+    /// The file does not start with the expected magic bytes
+    #[error("Invalid network file header, expected \"PJNN\".")]
+    InvalidHeader,
+    /// The hidden layer size in the file does not match the engine's
+    #[error("Invalid network hidden layer size: {0}.")]
+    WrongHiddenSize(usize),
+    /// The file does not have the expected size
+    #[error("Invalid network file size: {0} bytes.")]
+    WrongFileSize(usize),
+}
+
+/// Errors returned if training data cannot be read or written
+#[derive(Debug, Error)]
+pub enum DataErrorKind {
+    /// The file could not be read or written
+    #[error("Could not access the training data file.")]
+    Io(#[from] io::Error),
+    /// The positions could not be encoded
+    #[error("Could not encode the training data.")]
+    Encode(#[from] bincode::error::EncodeError),
+    /// The positions could not be decoded
+    #[error("Could not decode the training data.")]
+    Decode(#[from] bincode::error::DecodeError),
+}
+
 /// UGI engine errors
 #[derive(Debug, Error)]
 pub enum UgiErrorKind {
@@ -125,6 +211,15 @@ pub enum UgiErrorKind {
     /// Clap error
     #[error("Command parsing error.")]
     ClapError(#[from] clap::Error),
+    /// File reading error
+    #[error("Could not read the file.")]
+    ReadFile(#[from] io::Error),
+    /// Thread pool creation error
+    #[error("Could not build the thread pool.")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+    /// Evaluation trace requested while a network is used
+    #[error("The evaluation trace is not available when a network is used.")]
+    EvalTraceWithNetwork,
 }
 
 /// Gets the error traceback as a String vector.
@@ -0,0 +1,324 @@
+//! This module implements the C ABI of the engine (used by the C#/Unity client).
+//!
+//! The engine is exposed as an opaque handle created by [`pijersi_engine_new`] and destroyed by [`pijersi_engine_free`].
+//! The functions that can fail return a [`PijersiErrorCode`]. Strings are NUL-terminated UTF-8, the output strings are written to buffers allocated by the caller.
+//!
+//! No panic unwinds into the caller: the functions that return an error code return [`PijersiErrorCode::Panic`], [`pijersi_engine_new`] returns null and the queries return their default value.
+//!
+//! The C declarations are in `include/pijersi.h`, it must be kept in sync with this module.
+
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::engine::Engine;
+use crate::errors::RuntimeError;
+use crate::game::SearchLimits;
+use crate::logic::translate::action_to_string;
+use crate::search::Score;
+
+/// The error codes returned by the C ABI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PijersiErrorCode {
+    /// Success
+    Ok = 0,
+    /// A pointer argument is null
+    NullPointer = 1,
+    /// A string argument is not valid UTF-8
+    InvalidString = 2,
+    /// An argument is out of range
+    InvalidArgument = 3,
+    /// The output buffer is too small for the string and its NUL terminator
+    BufferTooSmall = 4,
+    /// The action is illegal (see [`RuntimeError::Rules`])
+    Rules = 5,
+    /// A string could not be parsed (see [`RuntimeError::Parse`])
+    Parse = 6,
+    /// UGI engine error (see [`RuntimeError::UGI`])
+    Ugi = 7,
+    /// Failed loading a network (see [`RuntimeError::Network`])
+    Network = 8,
+    /// Failed reading or writing training data (see [`RuntimeError::Data`])
+    Data = 9,
+    /// The search did not find an action (the game is over)
+    NoAction = 10,
+    /// The engine panicked (internal error)
+    Panic = 11,
+}
+
+impl From<&RuntimeError> for PijersiErrorCode {
+    fn from(error: &RuntimeError) -> Self {
+        match error {
+            RuntimeError::Rules(_) => Self::Rules,
+            RuntimeError::Parse(_) => Self::Parse,
+            RuntimeError::UGI(_) => Self::Ugi,
+            RuntimeError::Network(_) => Self::Network,
+            RuntimeError::Data(_) => Self::Data,
+        }
+    }
+}
+
+impl From<RuntimeError> for PijersiErrorCode {
+    fn from(error: RuntimeError) -> Self {
+        Self::from(&error)
+    }
+}
+
+/// Runs an operation and converts its result to the error code returned to C (a panic is caught and converted to [`PijersiErrorCode::Panic`]).
+fn to_error_code(operation: impl FnOnce() -> Result<(), PijersiErrorCode>) -> PijersiErrorCode {
+    match catch_unwind(AssertUnwindSafe(operation)) {
+        Ok(Ok(())) => PijersiErrorCode::Ok,
+        Ok(Err(code)) => code,
+        Err(_) => PijersiErrorCode::Panic,
+    }
+}
+
+/// Runs a query and returns its result, or the default value if it panics.
+fn query_or<T>(default: T, query: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(query)).unwrap_or(default)
+}
+
+/// Returns the engine behind a handle.
+///
+/// # Safety
+///
+/// The handle must be null or a valid handle returned by [`pijersi_engine_new`].
+unsafe fn engine_ref<'a>(handle: *const Engine) -> Result<&'a Engine, PijersiErrorCode> {
+    handle.as_ref().ok_or(PijersiErrorCode::NullPointer)
+}
+
+/// Returns the engine behind a handle (mutable).
+///
+/// # Safety
+///
+/// The handle must be null or a valid handle returned by [`pijersi_engine_new`], not used by another thread.
+unsafe fn engine_mut<'a>(handle: *mut Engine) -> Result<&'a mut Engine, PijersiErrorCode> {
+    handle.as_mut().ok_or(PijersiErrorCode::NullPointer)
+}
+
+/// Reads a NUL-terminated UTF-8 string.
+///
+/// # Safety
+///
+/// The pointer must be null or point to a NUL-terminated string.
+unsafe fn read_string<'a>(string: *const c_char) -> Result<&'a str, PijersiErrorCode> {
+    if string.is_null() {
+        return Err(PijersiErrorCode::NullPointer);
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| PijersiErrorCode::InvalidString)
+}
+
+/// Writes a string and its NUL terminator to a buffer.
+///
+/// # Safety
+///
+/// The buffer must be null or valid for writes of `size` bytes.
+unsafe fn write_string(
+    string: &str,
+    buffer: *mut c_char,
+    size: usize,
+) -> Result<(), PijersiErrorCode> {
+    if buffer.is_null() {
+        return Err(PijersiErrorCode::NullPointer);
+    }
+    let bytes = string.as_bytes();
+    if bytes.len() >= size {
+        return Err(PijersiErrorCode::BufferTooSmall);
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.cast(), bytes.len());
+    *buffer.add(bytes.len()) = 0;
+    Ok(())
+}
+
+/// Creates a new engine at the starting position, with its opening book and transposition table loaded.
+///
+/// Returns null if the engine could not be created. The handle must be destroyed with [`pijersi_engine_free`].
+#[no_mangle]
+pub extern "C" fn pijersi_engine_new() -> *mut Engine {
+    query_or(std::ptr::null_mut(), || {
+        let mut engine = Engine::new();
+        engine.load();
+        Box::into_raw(Box::new(engine))
+    })
+}
+
+/// Destroys an engine (does nothing if the handle is null).
+///
+/// # Safety
+///
+/// The handle must be null or a valid handle returned by [`pijersi_engine_new`], it cannot be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_free(handle: *mut Engine) {
+    if !handle.is_null() {
+        query_or((), || drop(Box::from_raw(handle)));
+    }
+}
+
+/// Sets the position from a Pijersi Standard Notation string.
+///
+/// Positions that cannot occur in a game are rejected (see [`crate::bitboard::Board::validate`]).
+///
+/// # Safety
+///
+/// The handle must be null or valid and `psn` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_set_position(
+    handle: *mut Engine,
+    psn: *const c_char,
+) -> PijersiErrorCode {
+    to_error_code(|| {
+        let engine = engine_mut(handle)?;
+        let psn = read_string(psn)?;
+        engine
+            .game
+            .set_string_state(psn)
+            .map_err(|e| RuntimeError::Parse(e).into())
+    })
+}
+
+/// Writes the Pijersi Standard Notation string of the position to the buffer.
+///
+/// # Safety
+///
+/// The handle must be null or valid and `buffer` must be null or valid for writes of `size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_get_position(
+    handle: *const Engine,
+    buffer: *mut c_char,
+    size: usize,
+) -> PijersiErrorCode {
+    to_error_code(|| {
+        let engine = engine_ref(handle)?;
+        write_string(&engine.game.get_string_state(), buffer, size)
+    })
+}
+
+/// Plays an action given as a string ("a1b1c1" or "a1b1" format).
+///
+/// # Safety
+///
+/// The handle must be null or valid and `action` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_play(
+    handle: *mut Engine,
+    action: *const c_char,
+) -> PijersiErrorCode {
+    to_error_code(|| {
+        let engine = engine_mut(handle)?;
+        let action = read_string(action)?;
+        engine.game.play_from_string(action).map_err(Into::into)
+    })
+}
+
+/// Writes the legal actions of the current player to the buffer, separated by spaces.
+///
+/// The size of the buffer needed for the actions and the NUL terminator is written to `required_size` (can be null), even if the buffer is too small: the call can then be repeated with a large enough buffer.
+///
+/// # Safety
+///
+/// The handle must be null or valid, `buffer` must be null or valid for writes of `size` bytes and `required_size` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_legal_actions(
+    handle: *const Engine,
+    buffer: *mut c_char,
+    size: usize,
+    required_size: *mut usize,
+) -> PijersiErrorCode {
+    to_error_code(|| {
+        let engine = engine_ref(handle)?;
+        let board = &engine.game.board;
+        let actions = board.available_player_actions(engine.game.current_player);
+        let actions_string = actions[0..actions.len()]
+            .iter()
+            .map(|&action| action_to_string(board, action))
+            .collect::<Vec<String>>()
+            .join(" ");
+        if let Some(required_size) = required_size.as_mut() {
+            *required_size = actions_string.len() + 1;
+        }
+        write_string(&actions_string, buffer, size)
+    })
+}
+
+/// Returns whether a player has won (false if the handle is null or if the engine panics).
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_is_win(handle: *const Engine) -> bool {
+    query_or(false, || {
+        engine_ref(handle).is_ok_and(|engine| engine.game.is_win())
+    })
+}
+
+/// Returns whether the game is drawn (false if the handle is null or if the engine panics).
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_is_draw(handle: *const Engine) -> bool {
+    query_or(false, || {
+        engine_ref(handle).is_ok_and(|engine| engine.game.is_draw())
+    })
+}
+
+/// Returns the winner (0 for the first player, 1 for the second player) or -1 if there is none (or if the handle is null or if the engine panics).
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_winner(handle: *const Engine) -> i32 {
+    query_or(-1, || {
+        match engine_ref(handle).map(|engine| engine.game.get_winner()) {
+            Ok(Some(player)) => player as i32,
+            _ => -1,
+        }
+    })
+}
+
+/// Searches the best action and writes it to the buffer ("a1b1c1" format, 7 bytes are enough) and its score to `score`.
+///
+/// The search is limited by depth and/or time in milliseconds, a limit of 0 means no limit (at least one limit is required).
+/// The score is from the point of view of the current player, `score` can be null if it is not needed.
+///
+/// # Safety
+///
+/// The handle must be null or valid, `buffer` must be null or valid for writes of `size` bytes and `score` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pijersi_engine_search(
+    handle: *const Engine,
+    depth: u64,
+    movetime: u64,
+    buffer: *mut c_char,
+    size: usize,
+    score: *mut Score,
+) -> PijersiErrorCode {
+    to_error_code(|| {
+        let engine = engine_ref(handle)?;
+        if depth == 0 && movetime == 0 {
+            return Err(PijersiErrorCode::InvalidArgument);
+        }
+        let limits = SearchLimits {
+            depth: (depth > 0).then_some(depth),
+            movetime: (movetime > 0).then_some(movetime),
+            nodes: None,
+        };
+        let result = engine
+            .search(&limits, None)
+            .ok_or(PijersiErrorCode::NoAction)?;
+        write_string(
+            &action_to_string(&engine.game.board, result.action.action()),
+            buffer,
+            size,
+        )?;
+        if let Some(score) = score.as_mut() {
+            *score = result.score;
+        }
+        Ok(())
+    })
+}
@@ -12,34 +12,81 @@
 //! 32  33  34  35  36  37  38
 //!   39  40  41  42  43  44
 //! ```
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
 use crate::bitboard::Board;
 use crate::errors::{ParseError, ParseErrorKind, RulesErrorKind, RuntimeError};
+use crate::hash::position::HashTrait;
 use crate::hash::search::SearchTable;
 use crate::logic::actions::{Action, ActionTrait};
 use crate::logic::rules::is_action_legal;
-use crate::logic::translate::{
-    action_to_string, player_to_string, string_to_action, string_to_player,
-};
+use crate::logic::translate::{player_to_string, string_to_action, string_to_player};
 use crate::logic::{Player, MAX_HALF_MOVES};
-use crate::search::alphabeta::search_iterative;
+use crate::search::alphabeta::{
+    search_iterative, search_lazy_smp, NodeLimit, SearchContext, SearchMode,
+};
+use crate::search::eval::{EvalWeights, MATE_THRESHOLD};
+use crate::search::info::{InfoCallback, SearchInfo};
+#[cfg(feature = "nnue")]
+use crate::search::nnue::Network;
 use crate::search::openings::OpeningBook;
+use crate::search::skill::{search_skill, skill_depth, MAX_SKILL_LEVEL, SKILL_TIME_DIVISOR};
+use crate::search::stats::SearchStats;
 use crate::search::Score;
 
 /// This struct represents the board options.
 ///
 /// It contains various parameters for the search engine:
 /// * Using the opening book
-/// * Printing the info logs during searches
+/// * Using late move reductions, null-move pruning and threat extensions
+/// * The way the search is spread over the threads
+/// * Searching deterministically
+/// * The skill level
+/// * The contempt (the score of draws)
+/// * Collecting the search statistics
+/// * The weights of the evaluation terms
+/// * Allowing unusual positions
+/// * The network used by the evaluation (if the `nnue` feature is enabled)
+///
+/// The options are serialized without the network, missing options are set to their default value when deserialized.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameOptions {
     /// Using the opening book
     pub use_book: bool,
     /// Using the hash table
     pub use_table: bool,
-    /// Printing the info logs during searches
-    pub verbose: bool,
+    /// Using late move reductions
+    pub use_lmr: bool,
+    /// Using null-move pruning
+    pub use_null_move: bool,
+    /// Using threat extensions
+    pub use_threat_extensions: bool,
+    /// The way the search is spread over the threads of the thread pool
+    pub search_mode: SearchMode,
+    /// Searching sequentially so that a given position, depth and transposition table state always yield the same result (overrides the search mode)
+    pub deterministic: bool,
+    /// The skill level, from 0 to [`MAX_SKILL_LEVEL`] (full strength), see [`crate::search::skill`]
+    pub skill_level: u64,
+    /// The contempt: draws are scored `-contempt` from the point of view of the engine (positive values avoid draws, negative values seek them)
+    pub contempt: Score,
+    /// Collecting the search statistics, they are reported at the end of the search (see [`SearchStats`])
+    pub debug: bool,
+    /// The weights of the evaluation terms (see [`crate::search::eval::EvalTerm`])
+    pub eval_weights: EvalWeights,
+    /// Allowing positions that cannot occur in a game (for composing puzzles) when setting the state from a string, see [`Board::validate`]
+    pub allow_unusual_positions: bool,
+    /// The network that replaces the piece-square tables in the evaluation (optional)
+    #[cfg(feature = "nnue")]
+    #[serde(skip)]
+    pub network: Option<Network>,
 }
 
 impl Default for GameOptions {
@@ -53,13 +100,67 @@ impl GameOptions {
     /// ```not_rust
     /// use_book: true
     /// use_table: true
-    /// verbose: true
+    /// use_lmr: true
+    /// use_null_move: true
+    /// use_threat_extensions: true
+    /// search_mode: SearchMode::Split
+    /// deterministic: false
+    /// skill_level: MAX_SKILL_LEVEL
+    /// contempt: 0
+    /// debug: false
+    /// eval_weights: all terms disabled
+    /// allow_unusual_positions: false
+    /// network: None
     /// ```
     pub const fn new() -> Self {
         Self {
             use_book: false,
             use_table: true,
-            verbose: true,
+            use_lmr: true,
+            use_null_move: true,
+            use_threat_extensions: true,
+            search_mode: SearchMode::Split,
+            deterministic: false,
+            skill_level: MAX_SKILL_LEVEL,
+            contempt: 0,
+            debug: false,
+            eval_weights: EvalWeights::NONE,
+            allow_unusual_positions: false,
+            #[cfg(feature = "nnue")]
+            network: None,
+        }
+    }
+}
+
+/// The limits of a search.
+///
+/// Without any limit, the search goes on until a forced win or loss is found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The maximum depth (optional)
+    pub depth: Option<u64>,
+    /// The maximum duration in milliseconds (optional)
+    pub movetime: Option<u64>,
+    /// The maximum number of nodes of the alphabeta search, the quiescence search nodes are not counted (optional)
+    pub nodes: Option<u64>,
+}
+
+impl SearchLimits {
+    /// Creates limits that search up to a given depth.
+    pub const fn depth(depth: u64) -> Self {
+        Self {
+            depth: Some(depth),
+            movetime: None,
+            nodes: None,
+        }
+    }
+
+    /// Creates limits that search for a given duration in milliseconds.
+    pub const fn movetime(movetime: u64) -> Self {
+        Self {
+            depth: None,
+            movetime: Some(movetime),
+            nodes: None,
         }
     }
 }
@@ -72,6 +173,12 @@ impl GameOptions {
 /// * Current half moves count
 /// * Current full moves count
 /// * Piece count
+/// * Previous positions since the last capture (used to detect repetitions)
+///
+/// It is serialized with its options, counters and previous boards (see [`GameOptions`] and [`Board`]).
+/// When deserialized, the board is validated unless unusual positions are allowed, each previous board must lead to the next one with a legal action without capture, and the hashes of the previous positions are computed again.
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "GameData")]
 pub struct Game {
     /// The board options
     pub options: GameOptions,
@@ -81,7 +188,90 @@ pub struct Game {
     pub current_player: Player,
     half_moves: u64,
     full_moves: u64,
+    #[serde(skip)]
     last_piece_count: u64,
+    #[serde(skip)]
+    history: Vec<usize>,
+    previous_boards: Vec<Board>,
+}
+
+/// The serialized fields of a [`Game`], validated when deserialized.
+#[derive(Deserialize)]
+struct GameData {
+    options: GameOptions,
+    board: Board,
+    current_player: Player,
+    half_moves: u64,
+    full_moves: u64,
+    previous_boards: Vec<Board>,
+}
+
+impl TryFrom<GameData> for Game {
+    type Error = String;
+
+    fn try_from(data: GameData) -> Result<Self, Self::Error> {
+        if data.current_player > 1 {
+            return Err(format!(
+                "invalid player {}, expected 0 or 1",
+                data.current_player
+            ));
+        }
+        if !data.options.allow_unusual_positions {
+            data.board
+                .validate()
+                .map_err(|kind| format!("invalid position: {kind}"))?;
+        }
+        if data.previous_boards.len() as u64 > data.half_moves {
+            return Err(format!(
+                "{} previous boards for {} half moves",
+                data.previous_boards.len(),
+                data.half_moves
+            ));
+        }
+        // The previous players alternate back from the current player
+        let n_previous = data.previous_boards.len();
+        let previous_players =
+            (0..n_previous).map(|index| (data.current_player + (n_previous - index) as Player) % 2);
+        let next_boards = data.previous_boards.iter().skip(1).chain([&data.board]);
+        for ((board, player), next_board) in data
+            .previous_boards
+            .iter()
+            .zip(previous_players.clone())
+            .zip(next_boards)
+        {
+            let leads_to_next = board.count_pieces() == next_board.count_pieces()
+                && board
+                    .available_player_actions(player)
+                    .into_iter()
+                    .any(|action| {
+                        let mut new_board = *board;
+                        new_board.play_action(action);
+                        new_board == *next_board
+                    });
+            if !leads_to_next {
+                return Err(format!(
+                    "no legal action without capture from the previous board {}",
+                    board.to_fen()
+                ));
+            }
+        }
+        let history = data
+            .previous_boards
+            .iter()
+            .zip(previous_players)
+            .map(|(board, player)| (board, player).hash())
+            .collect();
+        Ok(Self {
+            options: data.options,
+            board: data.board,
+            current_player: data.current_player,
+            half_moves: data.half_moves,
+            full_moves: data.full_moves,
+            last_piece_count: data.board.count_pieces(),
+            history,
+            previous_boards: data.previous_boards,
+        })
+    }
 }
 
 impl Default for Game {
@@ -100,6 +290,8 @@ impl Game {
             half_moves: 0u64,
             full_moves: 0u64,
             last_piece_count: 0u64,
+            history: vec![],
+            previous_boards: vec![],
         }
     }
 
@@ -115,6 +307,8 @@ impl Game {
         self.half_moves = 0;
         self.full_moves = 1;
         self.last_piece_count = self.board.count_pieces(); // 28 starting pieces (14 for each side)
+        self.history.clear();
+        self.previous_boards.clear();
     }
 
     /// Prints the current pieces on the board.
@@ -123,74 +317,205 @@ impl Game {
     }
 
     /// Searches and returns the action corresponding to the current board state according to the opening book (if it exists)
-    fn search_book(&self, opening_book: Option<&OpeningBook>) -> Option<(Action, u64, Score)> {
+    fn search_book(
+        &self,
+        opening_book: Option<&OpeningBook>,
+        context: &SearchContext,
+    ) -> Option<(Action, u64, Score)> {
         if let Some(opening_book) = opening_book {
             if let Some(&(action, score)) = opening_book.lookup(self) {
                 let depth = action.search_depth();
-                let action_string = action_to_string(&self.board, action);
-                if self.options.verbose {
-                    println!("info book depth {depth} score {score} pv {action_string}");
-                }
-                return Some((action, depth, score as Score));
+                let score = score as Score;
+                context.report(|| SearchInfo::Book {
+                    depth,
+                    score,
+                    action,
+                });
+                return Some((action, depth, score));
             }
         }
         None
     }
 
-    /// Searches and returns the best action at a given depth.
-    pub fn search_to_depth(
+    /// Returns the search parameters corresponding to the game options.
+    pub fn search_context<'a>(
+        &'a self,
+        end_time: Option<Instant>,
+        transposition_table: Option<&'a RwLock<SearchTable>>,
+    ) -> SearchContext<'a> {
+        let mut context = SearchContext::new(
+            end_time,
+            if self.options.use_table {
+                transposition_table
+            } else {
+                None
+            },
+            &self.options.eval_weights,
+        );
+        context.use_lmr = self.options.use_lmr;
+        context.use_null_move = self.options.use_null_move;
+        context.use_threat_extensions = self.options.use_threat_extensions;
+        context.parallel = !self.options.deterministic;
+        context.contempt = self.options.contempt;
+        context.half_moves = self.half_moves;
+        context.history = &self.history;
+        #[cfg(feature = "nnue")]
+        {
+            context.network = self.options.network.as_ref();
+        }
+        context
+    }
+
+    /// Searches and returns the best action within the given limits.
+    ///
+    /// The information events of the search are reported to the callback (if there is one), nothing is printed.
+    pub fn search(
         &self,
-        depth: u64,
+        limits: &SearchLimits,
         opening_book: Option<&OpeningBook>,
         transposition_table: Option<&RwLock<SearchTable>>,
+        info: Option<&InfoCallback>,
     ) -> Option<(Action, Score)> {
+        let end_time = limits
+            .movetime
+            .map(|movetime| Instant::now() + Duration::from_millis(movetime));
+        let node_limit = limits.nodes.map(NodeLimit::new);
+        let mut context = self.search_context(end_time, transposition_table);
+        context.info = info;
+        context.node_limit = node_limit.as_ref();
         if self.options.use_book {
-            if let Some((action, book_depth, score)) = self.search_book(opening_book) {
+            if let Some((action, book_depth, score)) = self.search_book(opening_book, &context) {
                 // TODO: start searching from the book move's depth and use it to sort the search order
-                if book_depth >= depth {
+                if !matches!(limits.depth, Some(depth) if depth > book_depth) {
                     return Some((action, score));
                 }
             }
         }
-        search_iterative(
-            &self.board,
-            self.current_player,
-            depth,
+        let max_depth = limits.depth.unwrap_or(u64::MAX);
+        if !self.options.debug {
+            return self.search_with_skill(max_depth, &context);
+        }
+        let stats = SearchStats::default();
+        let context = SearchContext {
+            stats: Some(&stats),
+            ..context
+        };
+        let result = self.search_with_skill(max_depth, &context);
+        context.report(|| SearchInfo::Stats(stats.summary()));
+        result
+    }
+
+    /// Searches and returns the best action at a given depth (without reporting information).
+    pub fn search_to_depth(
+        &self,
+        depth: u64,
+        opening_book: Option<&OpeningBook>,
+        transposition_table: Option<&RwLock<SearchTable>>,
+    ) -> Option<(Action, Score)> {
+        self.search(
+            &SearchLimits::depth(depth),
+            opening_book,
+            transposition_table,
             None,
-            self.options.verbose,
-            if self.options.use_table {
-                transposition_table
-            } else {
-                None
-            },
         )
     }
 
-    /// Searches and returns the best action after a given time.
+    /// Searches and returns the best action after a given time (without reporting information).
     pub fn search_to_time(
         &self,
         movetime: u64,
         opening_book: Option<&OpeningBook>,
         transposition_table: Option<&RwLock<SearchTable>>,
     ) -> Option<(Action, Score)> {
-        if self.options.use_book {
-            if let Some((action, _depth, score)) = self.search_book(opening_book) {
-                // TODO: start searching from the book move's depth and use it to sort the search order
-                return Some((action, score));
+        self.search(
+            &SearchLimits::movetime(movetime),
+            opening_book,
+            transposition_table,
+            None,
+        )
+    }
+
+    /// Searches the current board up to the chosen depth with the search mode of the game options.
+    ///
+    /// Deterministic searches are always run on a single thread.
+    ///
+    /// Below the maximum skill level, the depth is limited and the action is chosen randomly among the good actions (see [`crate::search::skill`]).
+    /// With a time limit, the main search only uses part of the time so that the skill search can run at the depth it completed.
+    /// Deterministic searches draw the random choice from the position hash.
+    fn search_with_skill(
+        &self,
+        max_depth: u64,
+        context: &SearchContext,
+    ) -> Option<(Action, Score)> {
+        let search = match self.options.search_mode {
+            _ if self.options.deterministic => search_iterative,
+            SearchMode::Split => search_iterative,
+            SearchMode::LazySmp => search_lazy_smp,
+        };
+        let level = self.options.skill_level;
+        let max_depth = max_depth.min(skill_depth(level));
+        if level >= MAX_SKILL_LEVEL {
+            return search(&self.board, self.current_player, max_depth, context);
+        }
+
+        // Part of the time budget is reserved for the skill search, which searches the actions at the last depth completed by the main search
+        let start_time = Instant::now();
+        let completed_depth = AtomicU64::new(0);
+        let report_iteration = |info: SearchInfo| {
+            if let SearchInfo::Iteration {
+                depth, bound: None, ..
+            } = info
+            {
+                completed_depth.fetch_max(depth, Relaxed);
             }
+            context.report(|| info);
+        };
+        let main_context = SearchContext {
+            end_time: context.end_time.map(|end_time| {
+                start_time + end_time.saturating_duration_since(start_time) / SKILL_TIME_DIVISOR
+            }),
+            info: Some(&report_iteration),
+            ..*context
+        };
+        let result = search(&self.board, self.current_player, max_depth, &main_context);
+        let best_score = match result {
+            Some((_action, score)) if score < MATE_THRESHOLD => score,
+            // Forced wins are always played
+            _ => return result,
+        };
+        let depth = completed_depth.load(Relaxed);
+
+        // The best action is played if the skill search is interrupted
+        let skill_result = if self.options.deterministic {
+            let seed = (&self.board, self.current_player).hash() as u64;
+            let mut rng = StdRng::seed_from_u64(seed);
+            search_skill(
+                &self.board,
+                self.current_player,
+                (depth, best_score),
+                level,
+                context,
+                &mut rng,
+            )
+        } else {
+            search_skill(
+                &self.board,
+                self.current_player,
+                (depth, best_score),
+                level,
+                context,
+                &mut rand::rng(),
+            )
+        };
+        if let Some((action, score)) = skill_result {
+            context.report(|| SearchInfo::Skill {
+                level,
+                depth,
+                score,
+                action,
+            });
         }
-        search_iterative(
-            &self.board,
-            self.current_player,
-            u64::MAX,
-            Some(Instant::now() + Duration::from_millis(movetime)),
-            self.options.verbose,
-            if self.options.use_table {
-                transposition_table
-            } else {
-                None
-            },
-        )
+        skill_result.or(result)
     }
 
     /// Get the current board state.
@@ -210,6 +535,8 @@ impl Game {
         self.half_moves = half_moves;
         self.full_moves = full_moves;
         self.last_piece_count = self.board.count_pieces();
+        self.history.clear();
+        self.previous_boards.clear();
     }
 
     /// Get the Pijersi Standard Notation of the current board state.
@@ -225,11 +552,19 @@ impl Game {
     }
 
     /// Sets the state of the board according to Pijersi Standard Notation data.
+    ///
+    /// The position is validated (see [`Board::validate`]) unless unusual positions are allowed in the options.
     pub fn set_string_state(&mut self, state_string: &str) -> Result<(), ParseError> {
         if let [board_string, player_string, half_moves_string, full_moves_string] =
             state_string.split(' ').collect::<Vec<&str>>()[..]
         {
             let new_board = Board::try_from_fen(board_string)?;
+            if !self.options.allow_unusual_positions {
+                new_board.validate().map_err(|kind| ParseError {
+                    kind: ParseErrorKind::InvalidPosition(kind),
+                    value: board_string.to_owned(),
+                })?;
+            }
             let player = string_to_player(player_string)?;
             let half_moves = half_moves_string.parse::<u64>().map_err(|err| ParseError {
                 kind: ParseErrorKind::InvalidInt(err),
@@ -259,6 +594,8 @@ impl Game {
     /// Plays the chosen action provided in `Action` representation.
     pub fn play(&mut self, action: Action) -> Result<(), RulesErrorKind> {
         if is_action_legal(&self.board, self.current_player, action) {
+            let cells_hash = (&self.board, self.current_player).hash();
+            let previous_board = self.board;
             self.board.play_action(action);
             if self.current_player == 1 {
                 self.full_moves += 1;
@@ -267,9 +604,13 @@ impl Game {
             let piece_count = self.board.count_pieces();
             if self.last_piece_count == piece_count {
                 self.half_moves += 1;
+                self.history.push(cells_hash);
+                self.previous_boards.push(previous_board);
             } else {
                 self.last_piece_count = piece_count;
                 self.half_moves = 0;
+                self.history.clear();
+                self.previous_boards.clear();
             }
             Ok(())
         } else {
@@ -6,7 +6,10 @@
 //! The engine is named Natural Selection. It uses the Alpha-Beta search to find the best move for a given position.
 
 pub mod bitboard;
+pub mod datagen;
+pub mod engine;
 pub mod errors;
+pub mod ffi;
 pub mod game;
 pub mod hash;
 pub mod logic;
@@ -4,8 +4,12 @@ pub mod actions;
 pub mod index;
 pub mod lookup;
 pub mod movegen;
+pub mod moves;
+pub mod narration;
 pub mod perft;
+pub mod random;
 pub mod rules;
+pub mod symmetry;
 pub mod translate;
 
 /// The number of cells in a board
@@ -18,7 +18,9 @@ use crate::{
 
 use super::{
     index::{CellIndex, CellIndexTrait, INDEX_MASK, INDEX_WIDTH},
+    moves::{Move, MoveKind},
     translate::action_to_string,
+    Player,
 };
 
 /// Size of the array that stores player actions
@@ -46,6 +48,10 @@ pub trait ActionTrait: Copy {
     fn search_depth(self) -> u64;
     /// Adds the last index of an action to itself
     fn add_last_index(self, index_end: CellIndex) -> Self;
+    /// Returns the action mirrored left-right (see [`Board::mirror`])
+    fn mirror(self) -> Self;
+    /// Returns the action rotated by 180° (see [`Board::flip_colours`])
+    fn flip_colours(self) -> Self;
 }
 
 impl ActionTrait for Action {
@@ -84,6 +90,20 @@ impl ActionTrait for Action {
     fn add_last_index(self, index_end: CellIndex) -> Self {
         self | (index_end << (2 * INDEX_WIDTH)) as Self
     }
+
+    /// The additional data (such as the search depth) is kept.
+    fn mirror(self) -> Self {
+        let (index_start, index_mid, index_end) = self.to_indices();
+        (self & !ACTION_MASK)
+            | Self::from_indices(index_start.mirror(), index_mid.mirror(), index_end.mirror())
+    }
+
+    /// The additional data (such as the search depth) is kept.
+    fn flip_colours(self) -> Self {
+        let (index_start, index_mid, index_end) = self.to_indices();
+        (self & !ACTION_MASK)
+            | Self::from_indices(index_start.rotate(), index_mid.rotate(), index_end.rotate())
+    }
 }
 
 /// This struct is a fixed-length array that stores player actions.
@@ -296,3 +316,111 @@ impl Board {
         }
     }
 }
+
+/// The kind of a single step of an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StepKind {
+    /// A piece or a whole stack moves
+    Move,
+    /// The top piece stacks onto an ally
+    Stack,
+    /// The top piece of a stack leaves it
+    Unstack,
+}
+
+/// A single step of an action (see [`ActionDescription`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActionStep {
+    /// The kind of the step
+    pub kind: StepKind,
+    /// The index of the start cell of the step
+    pub from: CellIndex,
+    /// The index of the end cell of the step
+    pub to: CellIndex,
+    /// The piece or stack that moves during the step
+    pub piece: Piece,
+    /// The piece or stack captured at the end cell of the step (if there is one)
+    pub captured: Option<Piece>,
+}
+
+/// The description of an action on a given board: its kind, its steps and whether it wins.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionDescription {
+    /// The kind of the action
+    pub kind: MoveKind,
+    /// The steps of the action (one or two)
+    pub steps: Vec<ActionStep>,
+    /// Whether the action wins the game
+    pub is_win: bool,
+}
+
+impl ActionDescription {
+    /// Returns the pieces captured by the action.
+    pub fn captures(&self) -> Vec<Piece> {
+        self.steps.iter().filter_map(|step| step.captured).collect()
+    }
+
+    /// Returns whether the action captures at least one piece.
+    pub fn is_capture(&self) -> bool {
+        self.steps.iter().any(|step| step.captured.is_some())
+    }
+}
+
+impl Board {
+    /// Returns the piece captured by the given piece of the player at the chosen index (if there is one).
+    fn captured_piece(&self, index: CellIndex, piece: Piece, player: Player) -> Option<Piece> {
+        (self.victims(piece) & self.capturable(player))
+            .get(index)
+            .then(|| self.get_piece(index))
+    }
+
+    /// Describes an action played on this board: its kind, the pieces moved and captured at each step and whether it wins.
+    ///
+    /// The action is assumed to be legal.
+    pub fn describe_action(&self, action: Action) -> ActionDescription {
+        let (index_start, index_mid, index_end) = action.to_indices();
+        let kind = Move::from(action).kind(self);
+        let piece_start = self.get_piece(index_start);
+        let player: Player = if piece_start.is_white() { 0 } else { 1 };
+        let top = piece_start.top();
+
+        let step = |kind: StepKind, from: CellIndex, to: CellIndex, piece: Piece| ActionStep {
+            kind,
+            from,
+            to,
+            piece,
+            captured: match kind {
+                StepKind::Stack => None,
+                StepKind::Move | StepKind::Unstack => self.captured_piece(to, piece, player),
+            },
+        };
+        let steps = match kind {
+            MoveKind::Move => vec![step(StepKind::Move, index_start, index_end, piece_start)],
+            MoveKind::Stack => vec![step(StepKind::Stack, index_start, index_end, top)],
+            MoveKind::Unstack => vec![step(StepKind::Unstack, index_start, index_end, top)],
+            MoveKind::StackMove => vec![
+                step(StepKind::Stack, index_start, index_mid, top),
+                step(
+                    StepKind::Move,
+                    index_mid,
+                    index_end,
+                    top.stack_on(self.get_piece(index_mid)),
+                ),
+            ],
+            MoveKind::MoveStack => vec![
+                step(StepKind::Move, index_start, index_mid, piece_start),
+                step(StepKind::Stack, index_mid, index_end, top),
+            ],
+            MoveKind::MoveUnstack => vec![
+                step(StepKind::Move, index_start, index_mid, piece_start),
+                step(StepKind::Unstack, index_mid, index_end, top),
+            ],
+        };
+
+        ActionDescription {
+            kind,
+            steps,
+            is_win: self.is_action_win(action, player),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Implements the Index trait with helper methods for board indices.
 
 use super::actions::Action;
+use super::translate::{coords_to_index, index_to_coords};
+use super::N_CELLS;
 
 /// A cell index is represented as a usize.
 pub type CellIndex = usize;
@@ -20,6 +22,10 @@ pub trait CellIndexTrait: Copy {
     fn is_white_home(self) -> bool;
     /// Returns true if the index is in the first row on black's side
     fn is_black_home(self) -> bool;
+    /// Returns the index of the cell mirrored left-right (null indices are unchanged)
+    fn mirror(self) -> Self;
+    /// Returns the index of the cell rotated by 180° (null indices are unchanged)
+    fn rotate(self) -> Self;
 }
 
 impl CellIndexTrait for usize {
@@ -37,4 +43,23 @@ impl CellIndexTrait for usize {
     fn is_black_home(self) -> bool {
         self <= 5
     }
+
+    #[inline(always)]
+    fn mirror(self) -> Self {
+        if self.is_null() {
+            return self;
+        }
+        let (i, j) = index_to_coords(self);
+        let n_columns = if i % 2 == 0 { 6 } else { 7 };
+        coords_to_index(i, n_columns - 1 - j)
+    }
+
+    #[inline(always)]
+    fn rotate(self) -> Self {
+        if self.is_null() {
+            self
+        } else {
+            N_CELLS - 1 - self
+        }
+    }
 }
@@ -145,9 +145,10 @@ impl Board {
             for index_mid in self.available_non_captures2(index_start) {
                 let half_action: Action = Action::from_indices_half(index_start, index_mid);
 
-                // 2-range move, capture on unstack
+                // 2-range move, capture or win on unstack, win on stack
                 for index_end in
                     self.available_captures_and_win1(index_mid, piece_start, current_player)
+                        | self.available_stack_wins(index_mid, piece_start, current_player)
                 {
                     player_actions.push(half_action.add_last_index(index_end));
                 }
@@ -173,9 +174,10 @@ impl Board {
             for index_mid in self.available_non_captures1(index_start) {
                 let half_action: Action = Action::from_indices_half(index_start, index_mid);
 
-                // 1-range move, capture on unstack
+                // 1-range move, capture or win on unstack, win on stack
                 for index_end in
                     self.available_captures_and_win1(index_mid, piece_start, current_player)
+                        | self.available_stack_wins(index_mid, piece_start, current_player)
                 {
                     player_actions.push(half_action.add_last_index(index_end));
                 }
@@ -204,9 +206,23 @@ impl Board {
             }
 
             // stack
+            let stack_wins = self.available_stack_wins(index_start, piece_start, current_player);
             for index_mid in self.available_stacks(index_start, piece_start) {
                 let half_action: Action = Action::from_indices_half(index_start, index_mid);
 
+                if stack_wins.get(index_mid) {
+                    // win on stack, then any 1-range or 2-range move
+                    for index_end in self.available_moves2(index_mid, piece_start)
+                        | self.available_moves1(index_mid, piece_start)
+                    {
+                        player_actions.push(half_action.add_last_index(index_end));
+                    }
+
+                    // win on stack only
+                    player_actions.push(Action::from_indices(index_start, index_start, index_mid));
+                    continue;
+                }
+
                 // stack, 1-range or 2-range capture
                 for index_end in
                     self.available_captures_and_win2(index_mid, piece_start, current_player)
@@ -217,9 +233,27 @@ impl Board {
             }
         } else {
             // 1-range first action
+            let stack_wins = self.available_stack_wins(index_start, piece_start, current_player);
             for index_mid in self.available_stacks(index_start, piece_start) {
                 let half_action: Action = Action::from_indices_half(index_start, index_mid);
 
+                if stack_wins.get(index_mid) {
+                    // win on stack, then any 1-range or 2-range move
+                    for index_end in self.available_moves2(index_mid, piece_start)
+                        | self.available_moves1(index_mid, piece_start)
+                        | (NEIGHBOURS2[index_mid] & self.available_moves1(index_start, piece_start))
+                    {
+                        player_actions.push(half_action.add_last_index(index_end));
+                    }
+
+                    // win on stack, 1-range move to starting position
+                    player_actions.push(half_action.add_last_index(index_start));
+
+                    // win on stack only
+                    player_actions.push(Action::from_indices(index_start, index_start, index_mid));
+                    continue;
+                }
+
                 // stack, 1-range or 2-range capture
                 for index_end in
                     self.available_captures_and_win2(index_mid, piece_start, current_player)
@@ -0,0 +1,177 @@
+//! Implements the `Move` type: a strongly typed action with a board-independent notation.
+//!
+//! [`Action`] stays the fast internal encoding used by the move generator and the search. A `Move` wraps an action without the additional data (such as the search depth) and provides named accessors.
+//!
+//! The notation of a move always has three coordinates: the start, the middle and the end cells of the action. The middle cell is equal to the end cell if the action has a single step.
+//! For example, `a5b5d4` stacks the piece on a5 onto b5 and moves the stack to d4, `b4b4c4` stacks or unstacks the top piece on b4 to c4 and `b4c4c4` moves the piece or stack on b4 to c4.
+//! Unlike the UGI notation (see [`crate::logic::translate::action_to_string`]), it does not depend on the board.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bitboard::Board;
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::piece::PieceTrait;
+
+use super::actions::{Action, ActionTrait, ACTION_MASK};
+use super::index::{CellIndex, CellIndexTrait, INDEX_NULL};
+use super::translate::{index_to_string, string_to_index};
+
+/// The kind of a move, as played on a given board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// A piece or a whole stack moves
+    Move,
+    /// A piece stacks onto an adjacent ally
+    Stack,
+    /// The top piece of a stack leaves it
+    Unstack,
+    /// A piece stacks onto an adjacent ally, then the new stack moves
+    StackMove,
+    /// A stack moves, then its top piece stacks onto an ally
+    MoveStack,
+    /// A stack moves, then its top piece leaves it
+    MoveUnstack,
+}
+
+/// A move: an [`Action`] without additional data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(Action);
+
+impl Move {
+    /// Creates a move from its start, middle (if there is one) and end indices.
+    pub fn new(start: CellIndex, mid: Option<CellIndex>, end: CellIndex) -> Self {
+        Self(Action::from_indices(start, mid.unwrap_or(INDEX_NULL), end))
+    }
+
+    /// Returns the index of the start cell.
+    pub fn start(self) -> CellIndex {
+        self.0.to_indices().0
+    }
+
+    /// Returns the index of the middle cell (if the move has two steps or is a stack or unstack).
+    pub fn mid(self) -> Option<CellIndex> {
+        let index_mid = self.0.to_indices().1;
+        (!index_mid.is_null()).then_some(index_mid)
+    }
+
+    /// Returns the index of the end cell.
+    pub fn end(self) -> CellIndex {
+        self.0.to_indices().2
+    }
+
+    /// Returns the underlying action.
+    pub fn action(self) -> Action {
+        self.0
+    }
+
+    /// Returns the kind of the move when played on the given board.
+    ///
+    /// Stacks and unstacks share the same encoding, so the kind depends on the pieces on the board.
+    pub fn kind(self, board: &Board) -> MoveKind {
+        let (index_start, index_mid, index_end) = self.0.to_indices();
+        if index_mid.is_null() {
+            return MoveKind::Move;
+        }
+        let piece_start = board.get_piece(index_start);
+        let is_ally = |index: CellIndex| {
+            let piece = board.get_piece(index);
+            !piece.is_empty() && piece.colour() == piece_start.colour()
+        };
+        // After a first move, the start cell is empty: a move that ends on it is an unstack
+        let is_end_ally = index_end != index_start && is_ally(index_end);
+        match (index_start == index_mid, is_ally(index_mid), is_end_ally) {
+            (false, true, _) => MoveKind::StackMove,
+            (true, _, true) => MoveKind::Stack,
+            (true, _, false) => MoveKind::Unstack,
+            (false, false, true) => MoveKind::MoveStack,
+            (false, false, false) => MoveKind::MoveUnstack,
+        }
+    }
+
+    /// Returns whether the indices of the move are valid cells.
+    fn is_valid(self) -> bool {
+        let (index_start, index_mid, index_end) = self.0.to_indices();
+        !index_start.is_null()
+            && !index_end.is_null()
+            && (!index_mid.is_null() || index_mid == INDEX_NULL)
+    }
+}
+
+impl From<Action> for Move {
+    /// Converts an action to a move, dropping its additional data (such as the search depth).
+    fn from(action: Action) -> Self {
+        Self(action & ACTION_MASK)
+    }
+}
+
+impl From<Move> for Action {
+    fn from(value: Move) -> Self {
+        value.0
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            index_to_string(self.start()),
+            index_to_string(self.mid().unwrap_or(self.end())),
+            index_to_string(self.end())
+        )
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    /// Parses the three-coordinate notation of a move (such as `a5b5d4`), without a board.
+    fn from_str(move_string: &str) -> Result<Self, Self::Err> {
+        if move_string.len() != 6 || !move_string.is_ascii() {
+            return Err(ParseError {
+                kind: ParseErrorKind::InvalidMove,
+                value: move_string.to_owned(),
+            });
+        }
+        let index_start = string_to_index(&move_string[0..2])?;
+        let index_mid = string_to_index(&move_string[2..4])?;
+        let index_end = string_to_index(&move_string[4..6])?;
+        Ok(Self::new(
+            index_start,
+            (index_mid != index_end).then_some(index_mid),
+            index_end,
+        ))
+    }
+}
+
+impl Serialize for Move {
+    /// Serializes the move notation for human-readable formats and the action otherwise.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let move_string = String::deserialize(deserializer)?;
+            move_string.parse().map_err(D::Error::custom)
+        } else {
+            let action = Action::deserialize(deserializer)?;
+            let new_move = Self(action);
+            if action & !ACTION_MASK == 0 && new_move.is_valid() {
+                Ok(new_move)
+            } else {
+                Err(D::Error::custom(format!("invalid action: {action}")))
+            }
+        }
+    }
+}
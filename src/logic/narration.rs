@@ -0,0 +1,199 @@
+//! Implements the plain text narration of actions and positions (used for accessibility).
+//!
+//! An action is narrated in the context of the board it is played on, for example:
+//! "White rock on c3 stacks onto paper on d3, then the stack moves to e4, capturing black scissors."
+
+use crate::bitboard::Board;
+use crate::piece::{
+    Piece, PieceTrait, COLOUR_WHITE, TYPE_PAPER, TYPE_ROCK, TYPE_SCISSORS, TYPE_WISE,
+};
+
+use super::actions::{Action, ActionStep, ActionTrait, StepKind};
+use super::index::{CellIndex, CellIndexTrait};
+use super::translate::{index_to_coords, index_to_string, ROW_LETTERS};
+use super::N_CELLS;
+
+/// Returns the name of a piece type.
+fn type_name(piece: Piece) -> &'static str {
+    match piece.r#type() {
+        TYPE_SCISSORS => "scissors",
+        TYPE_PAPER => "paper",
+        TYPE_ROCK => "rock",
+        TYPE_WISE => "wise",
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the name of a piece colour.
+fn colour_name(piece: Piece) -> &'static str {
+    if piece.colour() == COLOUR_WHITE {
+        "white"
+    } else {
+        "black"
+    }
+}
+
+/// Returns the name of a piece or stack without its colour: "rock" or "stack of rock on paper".
+fn piece_name(piece: Piece) -> String {
+    if piece.is_stack() {
+        format!(
+            "stack of {} on {}",
+            type_name(piece.top()),
+            type_name(piece.bottom())
+        )
+    } else {
+        type_name(piece).to_owned()
+    }
+}
+
+/// Returns the name of a piece or stack with its colour: "white rock" or "white stack of rock on paper".
+pub fn describe_piece(piece: Piece) -> String {
+    format!("{} {}", colour_name(piece), piece_name(piece))
+}
+
+/// Returns the clause of a step: "moves to d2, capturing black scissors", "stacks onto paper on c2" or "unstacks to d2".
+///
+/// The piece stacked onto is read on the board before the action: a stack never ends on the start cell of the action.
+fn step_clause(board: &Board, step: &ActionStep) -> String {
+    let cell = index_to_string(step.to);
+    let capture = match step.captured {
+        Some(piece) => format!(", capturing {}", describe_piece(piece)),
+        None => String::new(),
+    };
+    match step.kind {
+        StepKind::Move => format!("moves to {cell}{capture}"),
+        StepKind::Stack => format!(
+            "stacks onto {} on {cell}",
+            piece_name(board.get_piece(step.to))
+        ),
+        StepKind::Unstack => format!("unstacks to {cell}{capture}"),
+    }
+}
+
+/// Capitalizes the first letter of a sentence.
+fn capitalize(sentence: &str) -> String {
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Narrates an action in plain text, in the context of the board it is played on.
+///
+/// The action is assumed to be legal. Each step is described (move, stack or unstack) with the pieces it captures.
+pub fn narrate_action(board: &Board, action: Action) -> String {
+    let index_start = action.to_indices().0;
+    if index_start.is_null() {
+        return "No action.".to_owned();
+    }
+
+    let piece_start = board.get_piece(index_start);
+    let cell_start = index_to_string(index_start);
+    let subject = format!("{} on {cell_start}", describe_piece(piece_start));
+    let description = board.describe_action(action);
+
+    let mut narration = match description.steps[..] {
+        // A single step: the whole piece or stack moves, or its top piece stacks or unstacks
+        [step] if step.piece == piece_start => format!("{subject} {}", step_clause(board, &step)),
+        [step] => format!(
+            "{} from the stack on {cell_start} {}",
+            describe_piece(step.piece),
+            step_clause(board, &step)
+        ),
+        // Stack on an ally, then move the new stack
+        [first, second] if first.kind == StepKind::Stack => format!(
+            "{subject} {}, then the stack {}",
+            step_clause(board, &first),
+            step_clause(board, &second)
+        ),
+        // Move the piece or stack, then stack or unstack its top piece
+        [first, second] => format!(
+            "{subject} {}, then its {} {}",
+            step_clause(board, &first),
+            type_name(second.piece),
+            step_clause(board, &second)
+        ),
+        _ => unreachable!(),
+    };
+
+    if description.is_win {
+        narration.push_str(&format!(
+            ", reaching the last row: {} wins",
+            colour_name(piece_start)
+        ));
+    }
+    capitalize(&narration) + "."
+}
+
+/// Describes a position cell by cell, one row per line from the top row (black's side) to the bottom row (white's side).
+///
+/// For example: "Row g: g1 black scissors, g2 black paper, g6 black rock." or "Row e: empty."
+pub fn describe_cells(board: &Board) -> String {
+    let mut rows: Vec<Vec<String>> = vec![vec![]; ROW_LETTERS.len()];
+    for index in 0..N_CELLS {
+        let piece = board.get_piece(index);
+        if !piece.is_empty() {
+            let (i, _j) = index_to_coords(index);
+            rows[i].push(format!(
+                "{} {}",
+                index_to_string(index),
+                describe_piece(piece)
+            ));
+        }
+    }
+    rows.iter()
+        .zip(ROW_LETTERS)
+        .map(|(cells, letter)| {
+            if cells.is_empty() {
+                format!("Row {letter}: empty.")
+            } else {
+                format!("Row {letter}: {}.", cells.join(", "))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Describes a position grouped by piece, one colour per line (white first).
+///
+/// For example: "White: scissors on a1, b3; paper on a2; stack of rock on paper on b2."
+pub fn describe_pieces(board: &Board) -> String {
+    ["white", "black"]
+        .iter()
+        .map(|&colour| {
+            // The pieces are grouped by value: single pieces sort before stacks
+            let mut groups: Vec<(Piece, Vec<CellIndex>)> = vec![];
+            for index in 0..N_CELLS {
+                let piece = board.get_piece(index);
+                if piece.is_empty() || colour_name(piece) != colour {
+                    continue;
+                }
+                match groups
+                    .iter_mut()
+                    .find(|(group_piece, _)| *group_piece == piece)
+                {
+                    Some((_, indices)) => indices.push(index),
+                    None => groups.push((piece, vec![index])),
+                }
+            }
+            groups.sort_by_key(|&(piece, _)| (piece.is_stack(), piece.r#type(), piece.bottom()));
+            let groups = groups
+                .iter()
+                .map(|(piece, indices)| {
+                    let cells: Vec<String> = indices
+                        .iter()
+                        .map(|&index| index_to_string(index))
+                        .collect();
+                    format!("{} on {}", piece_name(*piece), cells.join(", "))
+                })
+                .collect::<Vec<String>>();
+            if groups.is_empty() {
+                format!("{}: no pieces.", capitalize(colour))
+            } else {
+                format!("{}: {}.", capitalize(colour), groups.join("; "))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,239 @@
+//! Implements a seeded generator of random legal positions (used for fuzzing, training data and puzzles).
+//!
+//! The positions are generated in one of two ways (see [`GenerationMethod`]):
+//! * Playing random legal actions from the starting position
+//! * Placing random material directly on the board, following the validity rules of [`Board::validate`]
+//!
+//! The generated positions can be filtered by outcome, side to move and material balance. The same seed and options always yield the same positions.
+//!
+//! ```ignore
+//! let options = GeneratorOptions {
+//!     player: Some(0),
+//!     ..GeneratorOptions::default()
+//! };
+//! for (board, player) in PositionGenerator::new(42, options).take(100) {
+//!     // ...
+//! }
+//! ```
+
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::bitboard::Board;
+use crate::piece::{
+    Piece, PieceTrait, BLACK_PAPER, BLACK_ROCK, BLACK_SCISSORS, BLACK_WISE, WHITE_PAPER,
+    WHITE_ROCK, WHITE_SCISSORS, WHITE_WISE,
+};
+
+use super::{Player, N_CELLS};
+
+/// The number of pieces of each player in the set
+pub const SET_SIZE: usize = 14;
+/// The maximum number of attempts at generating a position that passes the filters
+pub const MAX_ATTEMPTS: usize = 1000;
+/// The probability of stacking a placed piece onto an ally when placing the material directly
+const STACK_PROBABILITY: f64 = 0.25;
+
+/// The set of pieces of each player: 4 scissors, 4 paper, 4 rock and 2 wise
+const SETS: [[Piece; SET_SIZE]; 2] = [
+    [
+        WHITE_SCISSORS,
+        WHITE_SCISSORS,
+        WHITE_SCISSORS,
+        WHITE_SCISSORS,
+        WHITE_PAPER,
+        WHITE_PAPER,
+        WHITE_PAPER,
+        WHITE_PAPER,
+        WHITE_ROCK,
+        WHITE_ROCK,
+        WHITE_ROCK,
+        WHITE_ROCK,
+        WHITE_WISE,
+        WHITE_WISE,
+    ],
+    [
+        BLACK_SCISSORS,
+        BLACK_SCISSORS,
+        BLACK_SCISSORS,
+        BLACK_SCISSORS,
+        BLACK_PAPER,
+        BLACK_PAPER,
+        BLACK_PAPER,
+        BLACK_PAPER,
+        BLACK_ROCK,
+        BLACK_ROCK,
+        BLACK_ROCK,
+        BLACK_ROCK,
+        BLACK_WISE,
+        BLACK_WISE,
+    ],
+];
+
+/// The way the random positions are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationMethod {
+    /// Plays random legal actions from the starting position (fewer if the game ends before)
+    Playout {
+        /// The number of actions to play
+        plies: u64,
+    },
+    /// Places a random part of each player's set on random cells, stacking some pieces on their allies, with a random player to move
+    Placement {
+        /// The maximum number of pieces of each player (at least 1, at most [`SET_SIZE`])
+        max_pieces: usize,
+    },
+}
+
+/// The parameters of the random position generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// The way the positions are generated
+    pub method: GenerationMethod,
+    /// Accepting positions where a player has already won
+    pub allow_won: bool,
+    /// The player to move (optional)
+    pub player: Option<Player>,
+    /// The range of the material balance: the number of white pieces minus the number of black pieces, a stack counts as two pieces (optional)
+    pub material_balance: Option<RangeInclusive<i64>>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            method: GenerationMethod::Playout { plies: 20 },
+            allow_won: false,
+            player: None,
+            material_balance: None,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    /// Returns true if the position passes the filters.
+    pub fn accepts(&self, board: &Board, player: Player) -> bool {
+        let is_balance_accepted = match &self.material_balance {
+            Some(range) => range.contains(&material_balance(board)),
+            None => true,
+        };
+        (self.allow_won || board.get_winner().is_none())
+            && (self.player.is_none() || self.player == Some(player))
+            && is_balance_accepted
+    }
+}
+
+/// Returns the number of white pieces minus the number of black pieces (a stack counts as two pieces).
+pub fn material_balance(board: &Board) -> i64 {
+    // White pieces are in the bitboards 0 to 3 (top) and 8 to 11 (bottom), black pieces in the others
+    board
+        .0
+        .iter()
+        .enumerate()
+        .map(|(k, bitboard)| {
+            let count = bitboard.0.count_ones() as i64;
+            if k & 4 == 0 {
+                count
+            } else {
+                -count
+            }
+        })
+        .sum()
+}
+
+/// A seeded generator of random legal positions.
+///
+/// It is an iterator over the generated positions and the player to move, it stops if no position passing the filters is found in [`MAX_ATTEMPTS`] attempts.
+pub struct PositionGenerator {
+    rng: StdRng,
+    options: GeneratorOptions,
+}
+
+impl PositionGenerator {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64, options: GeneratorOptions) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            options,
+        }
+    }
+
+    /// Generates a position passing the filters (in at most [`MAX_ATTEMPTS`] attempts).
+    pub fn generate(&mut self) -> Option<(Board, Player)> {
+        for _ in 0..MAX_ATTEMPTS {
+            let (board, player) = match self.options.method {
+                GenerationMethod::Playout { plies } => self.playout(plies),
+                GenerationMethod::Placement { max_pieces } => self.placement(max_pieces),
+            };
+            if self.options.accepts(&board, player) {
+                return Some((board, player));
+            }
+        }
+        None
+    }
+
+    /// Plays random legal actions from the starting position.
+    fn playout(&mut self, plies: u64) -> (Board, Player) {
+        let mut board = Board::EMPTY;
+        board.init();
+        let mut player = 0;
+        for _ in 0..plies {
+            let actions = board.available_player_actions(player);
+            if actions.is_empty() || board.get_winner().is_some() {
+                break;
+            }
+            board.play_action(actions[self.rng.random_range(0..actions.len())]);
+            player = 1 - player;
+        }
+        (board, player)
+    }
+
+    /// Places random material on the board.
+    fn placement(&mut self, max_pieces: usize) -> (Board, Player) {
+        let mut board = Board::EMPTY;
+        for set in SETS {
+            let mut pieces = set;
+            pieces.shuffle(&mut self.rng);
+            let n_pieces = self.rng.random_range(1..=max_pieces.clamp(1, SET_SIZE));
+            for &piece in &pieces[..n_pieces] {
+                // A piece can stack onto a single ally, only a wise can stack onto a wise
+                let bottoms: Vec<usize> = (0..N_CELLS)
+                    .filter(|&index| {
+                        let bottom = board.get_piece(index);
+                        !bottom.is_empty()
+                            && !bottom.is_stack()
+                            && bottom.colour() == piece.colour()
+                            && (!piece.is_wise() || bottom.is_wise())
+                    })
+                    .collect();
+                if !bottoms.is_empty() && self.rng.random_bool(STACK_PROBABILITY) {
+                    let index = random_index(&mut self.rng, &bottoms);
+                    let bottom = board.get_piece(index);
+                    board.unset_piece(index, bottom);
+                    board.set_piece(index, piece.stack_on(bottom));
+                } else {
+                    let empty: Vec<usize> = (0..N_CELLS)
+                        .filter(|&index| board.get_piece(index).is_empty())
+                        .collect();
+                    board.set_piece(random_index(&mut self.rng, &empty), piece);
+                }
+            }
+        }
+        (board, self.rng.random_range(0..2))
+    }
+}
+
+/// Chooses a random index in a non-empty list.
+fn random_index(rng: &mut StdRng, indices: &[usize]) -> usize {
+    indices[rng.random_range(0..indices.len())]
+}
+
+impl Iterator for PositionGenerator {
+    type Item = (Board, Player);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generate()
+    }
+}
@@ -1,14 +1,15 @@
 //! Implements the rules to check if an action is valid or not.
 use crate::{
     bitboard::{Bitboard, Board},
-    piece::{Piece, PieceTrait, COLOUR_MASK, TYPE_MASK},
+    errors::InvalidPositionKind,
+    piece::{Piece, PieceTrait, COLOUR_MASK, PIECE_BIT, TYPE_MASK, TYPE_WISE},
 };
 
 use super::{
     actions::{Action, ActionTrait, ACTION_MASK},
     index::{CellIndex, CellIndexTrait, INDEX_NULL},
     lookup::{BLOCKER_MASKS, MAGICS, NEIGHBOURS1},
-    Player,
+    Player, N_CELLS,
 };
 
 const WHITE_WIN_MASK: Bitboard = Bitboard(0b000000000000000000000000000000000000000111111);
@@ -102,7 +103,7 @@ impl Board {
         player: Player,
     ) -> Bitboard {
         let neighbours = NEIGHBOURS1[index];
-        neighbours & (self.victims(piece) | win_mask(player))
+        neighbours & (self.victims(piece) | (win_mask(player) & !self.all()))
     }
 
     /// Returns a bitboard with the available range-2 captures for the piece at the given index.
@@ -113,7 +114,12 @@ impl Board {
         player: Player,
     ) -> Bitboard {
         let blockers = BLOCKER_MASKS[index] & !self.all();
-        blockers.get_magic(index) & (self.victims(piece) | win_mask(player))
+        blockers.get_magic(index) & (self.victims(piece) | (win_mask(player) & !self.all()))
+    }
+
+    /// Returns a bitboard with the available stacks for the piece at the given index that reach the last row.
+    pub fn available_stack_wins(&self, index: CellIndex, piece: Piece, player: Player) -> Bitboard {
+        self.available_stacks(index, piece) & win_mask(player)
     }
 
     /// Returns a bitboard with the available range-1 non-capture actions for the piece at the given index.
@@ -128,6 +134,13 @@ impl Board {
         blockers.get_magic(index) & !self.all()
     }
 
+    /// Returns true if the player has at least one legal action that wins immediately.
+    pub fn has_winning_action(&self, player: Player) -> bool {
+        self.available_player_captures_and_wins(player)
+            .into_iter()
+            .any(|action| self.is_action_win(action, player))
+    }
+
     /// Returns true if the current position is winning for one of the players.
     pub fn is_win(&self) -> bool {
         (self.white_not_wise() & WHITE_WIN_MASK).0 != 0
@@ -175,4 +188,40 @@ impl Board {
             .map(|bitboard| bitboard.0.count_ones() as u64)
             .sum()
     }
+
+    /// Checks that the position can occur in a game.
+    ///
+    /// The stacks must be made of pieces of the same colour with no wise on top of another piece type, each player has at most the pieces of the set (4 scissors, 4 paper, 4 rock and 2 wise) and no player has already won.
+    pub fn validate(&self) -> Result<(), InvalidPositionKind> {
+        // Pieces are counted by colour and type (the 3 least significant bits)
+        let mut counts = [0usize; 8];
+        for index in 0..N_CELLS {
+            let piece = self.get_piece(index);
+            if piece.is_empty() {
+                continue;
+            }
+            if piece.is_stack() {
+                let (top, bottom) = (piece.top(), piece.bottom());
+                if top.colour() != bottom.colour() {
+                    return Err(InvalidPositionKind::MixedStack(index));
+                }
+                if top.is_wise() && !bottom.is_wise() {
+                    return Err(InvalidPositionKind::WiseOnNonWise(index));
+                }
+                counts[(bottom & 0b0111) as usize] += 1;
+            }
+            counts[(piece.top() & 0b0111) as usize] += 1;
+        }
+        for (kind, &count) in counts.iter().enumerate() {
+            let piece = PIECE_BIT | kind as Piece;
+            let max = if piece.r#type() == TYPE_WISE { 2 } else { 4 };
+            if count > max {
+                return Err(InvalidPositionKind::TooManyPieces { piece, count, max });
+            }
+        }
+        match self.get_winner() {
+            Some(player) => Err(InvalidPositionKind::AlreadyWon(player)),
+            None => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,94 @@
+//! Implements the symmetries of the board and the canonical form of positions.
+//!
+//! The rules are invariant under two transforms: mirroring the board left-right ([`Board::mirror`]) and rotating it by 180° while swapping the colours of the pieces ([`Board::flip_colours`], the other player is then to move).
+//! Both transforms are their own inverse, so a position has up to four equivalent forms.
+
+use crate::bitboard::Board;
+
+use super::actions::{Action, ActionTrait};
+use super::Player;
+
+/// A symmetry of the board: a combination of mirroring and flipping the colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// No transform
+    Identity,
+    /// Left-right mirroring
+    Mirror,
+    /// 180° rotation with swapped colours
+    FlipColours,
+    /// Left-right mirroring and 180° rotation with swapped colours
+    MirrorFlipColours,
+}
+
+impl Symmetry {
+    /// All the symmetries
+    pub const ALL: [Self; 4] = [
+        Self::Identity,
+        Self::Mirror,
+        Self::FlipColours,
+        Self::MirrorFlipColours,
+    ];
+
+    /// Returns true if the symmetry mirrors the board left-right.
+    pub const fn mirrors(self) -> bool {
+        matches!(self, Self::Mirror | Self::MirrorFlipColours)
+    }
+
+    /// Returns true if the symmetry swaps the colours (and the player to move).
+    pub const fn flips_colours(self) -> bool {
+        matches!(self, Self::FlipColours | Self::MirrorFlipColours)
+    }
+
+    /// Applies the symmetry to a board.
+    pub fn apply_board(self, board: &Board) -> Board {
+        let board = if self.mirrors() {
+            board.mirror()
+        } else {
+            *board
+        };
+        if self.flips_colours() {
+            board.flip_colours()
+        } else {
+            board
+        }
+    }
+
+    /// Applies the symmetry to an action.
+    pub fn apply_action(self, action: Action) -> Action {
+        let action = if self.mirrors() {
+            action.mirror()
+        } else {
+            action
+        };
+        if self.flips_colours() {
+            action.flip_colours()
+        } else {
+            action
+        }
+    }
+
+    /// Applies the symmetry to a player.
+    pub const fn apply_player(self, player: Player) -> Player {
+        if self.flips_colours() {
+            1 - player
+        } else {
+            player
+        }
+    }
+}
+
+impl Board {
+    /// Returns the canonical form of the position and the symmetry that transforms the position into it.
+    ///
+    /// In the canonical form, white is to move and the board is the smallest (comparing the raw bitboards) of the two candidate forms.
+    /// Equivalent positions have the same canonical form. Since the symmetries are their own inverse, the same symmetry converts the actions of the canonical form back to the original position.
+    pub fn canonical(&self, current_player: Player) -> (Board, Symmetry) {
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| symmetry.apply_player(current_player) == 0)
+            .map(|symmetry| (symmetry.apply_board(self), symmetry))
+            .min_by_key(|(board, _)| board.0.map(|bitboard| bitboard.0))
+            .unwrap()
+    }
+}
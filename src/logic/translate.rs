@@ -22,7 +22,8 @@ use super::{
     Player, N_CELLS,
 };
 
-const ROW_LETTERS: [char; 7] = ['g', 'f', 'e', 'd', 'c', 'b', 'a'];
+/// The row letters from the top row (black's side) to the bottom row (white's side)
+pub const ROW_LETTERS: [char; 7] = ['g', 'f', 'e', 'd', 'c', 'b', 'a'];
 
 /// Converts a character to its corresponding piece (if it exists).
 pub const fn char_to_piece(piece_char: char) -> Option<Piece> {
@@ -78,7 +79,7 @@ pub const fn index_to_coords(index: CellIndex) -> (CellIndex, CellIndex) {
 }
 
 /// Converts a "a1" style string coordinate into an index.
-fn string_to_index(cell_string: &str) -> Result<CellIndex, ParseError> {
+pub fn string_to_index(cell_string: &str) -> Result<CellIndex, ParseError> {
     let mut iterator = cell_string.chars();
 
     // Guaranteed to match regex "\w\d", no handling needed.
@@ -130,23 +131,36 @@ pub fn index_to_string(index: CellIndex) -> String {
     ROW_LETTERS[i].to_string() + &(j + 1).to_string()
 }
 
+/// Draws a string for each cell using the layout of the Pijersi board.
+///
+/// The cell strings are right-aligned to the chosen width and separated by spaces, the rows with 6 cells are shifted by half a cell.
+/// There is no line break after the last row.
+pub fn cells_to_pretty_string(
+    cell_width: usize,
+    cell_string: impl Fn(CellIndex) -> String,
+) -> String {
+    let shift = " ".repeat(cell_width.div_ceil(2));
+    let mut pretty_string = shift.clone();
+    for index in 0..N_CELLS {
+        pretty_string += &format!("{:>cell_width$} ", cell_string(index));
+        if [5, 12, 18, 25, 31, 38].contains(&index) {
+            pretty_string += "\n";
+            if [12, 25, 38].contains(&index) {
+                pretty_string += &shift;
+            }
+        }
+    }
+    pretty_string
+}
+
 impl Bitboard {
     /// Converts the bitboard to a pretty formatted string.
     ///
     /// It shows how the bitboard looks on a Pijersi board.
     pub fn to_pretty_string(&self) -> String {
-        let mut pretty_string = " ".to_owned();
-        for i in 0..45 {
-            pretty_string += if self.get(i) { "X  " } else { ".  " };
-            if [5, 12, 18, 25, 31, 38].contains(&i) {
-                pretty_string += "\n";
-                if [12, 25, 38].contains(&i) {
-                    pretty_string += " ";
-                }
-            }
-        }
-
-        pretty_string
+        cells_to_pretty_string(2, |index| {
+            if self.get(index) { "X " } else { ". " }.to_owned()
+        })
     }
 }
 
@@ -186,47 +200,57 @@ impl Board {
     }
 
     /// Reads a Pijersi Standard Notation string to apply its state to the board.
+    ///
+    /// Only the notation is checked, use [`Board::validate`] to check that the position can occur in a game.
     pub fn try_from_fen(board_string: &str) -> Result<Self, ParseError> {
+        let error = |kind: InvalidPositionKind| ParseError {
+            kind: ParseErrorKind::InvalidPosition(kind),
+            value: board_string.to_owned(),
+        };
         let cell_lines: Vec<&str> = board_string.split('/').collect();
-        if cell_lines.len() == 7 {
-            let mut cursor: CellIndex = 0;
-            let mut new_board = Board::EMPTY;
-            for &cell_line in &cell_lines {
-                let mut j: usize = 0;
-                while j < cell_line.chars().count() {
-                    if let Some(top_char) = char_to_piece(cell_line.chars().nth(j).unwrap()) {
-                        if cell_line.chars().nth(j + 1).unwrap() == '-' {
-                            new_board.set_piece(
-                                cursor,
-                                char_to_piece(cell_line.chars().nth(j).unwrap()).unwrap(),
-                            );
-                        } else {
-                            new_board.set_piece(
-                                cursor,
-                                char_to_piece(cell_line.chars().nth(j + 1).unwrap())
-                                    .unwrap()
-                                    .stack_on(top_char),
-                            );
-                        }
-                        j += 2;
-                        cursor += 1;
+        if cell_lines.len() != 7 {
+            return Err(error(InvalidPositionKind::WrongLineNumber(
+                cell_lines.len(),
+            )));
+        }
+        let mut new_board = Board::EMPTY;
+        for (i, &cell_line) in cell_lines.iter().enumerate() {
+            let n_columns: usize = if i % 2 == 0 { 6 } else { 7 };
+            let mut j: usize = 0;
+            let mut chars = cell_line.chars();
+            while let Some(first_char) = chars.next() {
+                if let Some(jump) = first_char.to_digit(10).filter(|&jump| jump > 0) {
+                    j += jump as usize;
+                    continue;
+                }
+                // A cell is written as two characters: bottom then top, or piece then '-'
+                let bottom = char_to_piece(first_char)
+                    .filter(|piece| !piece.is_empty())
+                    .ok_or(error(InvalidPositionKind::InvalidCharacter(first_char)))?;
+                let second_char = chars
+                    .next()
+                    .ok_or(error(InvalidPositionKind::InvalidCharacter(first_char)))?;
+                let top = char_to_piece(second_char)
+                    .ok_or(error(InvalidPositionKind::InvalidCharacter(second_char)))?;
+                if j < n_columns {
+                    let index = coords_to_index(i, j);
+                    if top.is_empty() {
+                        new_board.set_piece(index, bottom);
                     } else {
-                        let jump =
-                            cell_line.chars().nth(j).unwrap().to_digit(10).unwrap() as CellIndex;
-                        j += 1;
-                        cursor += jump;
+                        new_board.set_piece(index, top.stack_on(bottom));
                     }
                 }
+                j += 1;
+            }
+            if j != n_columns {
+                return Err(error(InvalidPositionKind::WrongCellNumber {
+                    line: i,
+                    count: j,
+                    expected: n_columns,
+                }));
             }
-            Ok(new_board)
-        } else {
-            Err(ParseError {
-                kind: ParseErrorKind::InvalidPosition(InvalidPositionKind::WrongLineNumber(
-                    cell_lines.len(),
-                )),
-                value: board_string.to_owned(),
-            })
         }
+        Ok(new_board)
     }
 
     /// Converts the cells to a pretty formatted string.
@@ -242,9 +266,8 @@ impl Board {
     ///  R- P- S- R- P- S-
     /// ```
     pub fn to_pretty_string(&self) -> String {
-        let mut pretty_string = " ".to_owned();
-        for i in 0..N_CELLS {
-            let piece = self.get_piece(i);
+        cells_to_pretty_string(2, |index| {
+            let piece = self.get_piece(index);
             let top_piece: Piece = piece.top();
             let bottom_piece: Piece = piece.bottom();
             let char1: char = match top_piece {
@@ -275,17 +298,8 @@ impl Board {
                     _ => '?',
                 }
             };
-            pretty_string += &format!("{char1}{char2} ");
-
-            if [5, 12, 18, 25, 31, 38].contains(&i) {
-                pretty_string += "\n";
-                if [12, 25, 38].contains(&i) {
-                    pretty_string += " ";
-                }
-            }
-        }
-
-        pretty_string
+            format!("{char1}{char2}")
+        })
     }
 }
 
@@ -351,8 +365,10 @@ impl TryFrom<&str> for Board {
 }
 
 /// Converts a string (a1b1c1 style) move to the native triple-index format.
+///
+/// The capture markers of [`action_to_string_with_captures`] are ignored.
 pub fn string_to_action(board: &Board, action_string: &str) -> Result<Action, ParseError> {
-    let action_pattern = Regex::new(r"^(\w\d)(\w\d)?(\w\d)$").unwrap();
+    let action_pattern = Regex::new(r"^(\w\d)x?(\w\d)?x?(\w\d)$").unwrap();
 
     let action_captures = action_pattern.captures(action_string).ok_or(ParseError {
         kind: ParseErrorKind::InvalidAction,
@@ -384,12 +400,40 @@ pub fn string_to_action(board: &Board, action_string: &str) -> Result<Action, Pa
 
 /// Converts a native triple-index move into the string (a1b1c1 style) format.
 pub fn action_to_string(board: &Board, action: Action) -> String {
+    action_to_string_marked(board, action, false)
+}
+
+/// Converts a native triple-index move into the string (a1b1c1 style) format, with an `x` before each cell where a piece is captured (such as `a5xb5d4`).
+///
+/// The marked strings are also accepted by [`string_to_action`].
+pub fn action_to_string_with_captures(board: &Board, action: Action) -> String {
+    action_to_string_marked(board, action, true)
+}
+
+/// Converts a native triple-index move into the string (a1b1c1 style) format, with optional capture markers.
+fn action_to_string_marked(board: &Board, action: Action, mark_captures: bool) -> String {
     let (index_start, index_mid, index_end) = action.to_indices();
 
     if index_start.is_null() {
         return String::new();
     }
 
+    let (capture_mid, capture_end) = if mark_captures {
+        let steps = board.describe_action(action).steps;
+        let is_captured = |index: CellIndex| {
+            steps
+                .iter()
+                .any(|step| step.to == index && step.captured.is_some())
+        };
+        (
+            !index_mid.is_null() && is_captured(index_mid),
+            is_captured(index_end),
+        )
+    } else {
+        (false, false)
+    };
+    let marker = |is_capture: bool| if is_capture { "x" } else { "" };
+
     let action_string_start: String = index_to_string(index_start);
     let action_string_end: String = index_to_string(index_end);
 
@@ -408,7 +452,14 @@ pub fn action_to_string(board: &Board, action: Action) -> String {
         index_to_string(index_mid)
     };
 
-    format!("{action_string_start}{action_string_mid}{action_string_end}")
+    let marker_mid = if action_string_mid.is_empty() || index_mid.is_null() {
+        ""
+    } else {
+        marker(capture_mid)
+    };
+    let marker_end = marker(capture_end);
+
+    format!("{action_string_start}{marker_mid}{action_string_mid}{marker_end}{action_string_end}")
 }
 
 /// Parses the player argument: `"w"` -> `Ok(0)`, `"b"` -> `Ok(1)`
@@ -4,11 +4,6 @@ use pijersi_rs::ugi::UgiEngine;
 
 /// Runs the UGI protocol engine
 fn main() {
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(8)
-        .build_global()
-        .unwrap();
-
     let mut ugi_engine = UgiEngine::new();
     loop {
         let mut command = String::new();
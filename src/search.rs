@@ -2,16 +2,36 @@
 
 use std::sync::atomic::AtomicI16;
 
+use serde::{Deserialize, Serialize};
+
+use crate::logic::moves::Move;
+
 pub mod alphabeta;
 pub mod eval;
+pub mod info;
 pub mod lookup;
+#[cfg(feature = "nnue")]
+pub mod nnue;
 pub mod openings;
+pub mod ordering;
+pub mod skill;
+pub mod stats;
+pub mod suite;
 
 /// The score is represented by a i16 value.
 pub type Score = i16;
 /// The atomic score is represented by a AtomicI16 value.
 pub type AtomicScore = AtomicI16;
 
+/// The result of a search: the best action and its score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// The best action
+    pub action: Move,
+    /// The score of the best action from the point of view of the current player
+    pub score: Score,
+}
+
 /// The type of the node. It is used to determine if the score is exact, lower-bound, or higher bound.
 /// See <https://www.chessprogramming.org/Node_Types>
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -1,23 +1,30 @@
 //! This module implements the alphabeta search that chooses the best move
 
 use std::cmp::{max, min};
-use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicU64};
 use std::sync::RwLock;
 use std::time::Instant;
 
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::bitboard::Board;
 use crate::hash::position::HashTrait;
 use crate::hash::search::SearchTable;
-use crate::logic::actions::{Action, ActionTrait, Actions, AtomicAction};
+use crate::logic::actions::{Action, ActionTrait, Actions, AtomicAction, MAX_PLAYER_ACTIONS};
 use crate::logic::index::CellIndexTrait;
-use crate::logic::translate::action_to_string;
-use crate::logic::Player;
+use crate::logic::{Player, MAX_HALF_MOVES};
 use crate::utils::{argsort, reverse_argsort};
 
-use super::eval::{evaluate_position, evaluate_position_incremental, quiescence_search, MAX_SCORE};
+use super::eval::{
+    mate_distance, quiescence_search, win_score, EvalWeights, StaticEval, MATE_THRESHOLD, MAX_SCORE,
+};
+use super::info::{InfoCallback, ScoreBound, SearchInfo};
+#[cfg(feature = "nnue")]
+use super::nnue::Network;
+use super::ordering::SearchHeuristics;
+use super::stats::SearchStats;
 use super::{AtomicScore, NodeType, Score};
 
 /// Starting beta value for the alphabeta search (starting alpha is equal to -beta)
@@ -25,8 +32,27 @@ pub const BASE_BETA: Score = 8_192;
 /// Starting alpha value for the alphabeta search (starting alpha is equal to -beta)
 pub const BASE_ALPHA: Score = -BASE_BETA;
 
-#[cfg(feature = "nps-count")]
-use std::sync::atomic::AtomicU64;
+/// Minimum depth at which null-move pruning is used
+const NULL_MOVE_MIN_DEPTH: u64 = 3;
+/// Depth reduction of the null-move search
+const NULL_MOVE_REDUCTION: u64 = 2;
+/// Null-move pruning is skipped if the current player has fewer non-wise pieces than this (zugzwang guard)
+const NULL_MOVE_MIN_PIECES: u32 = 4;
+/// Minimum depth at which late move reductions are used
+const LMR_MIN_DEPTH: u64 = 3;
+/// Index (in the sorted actions) from which quiet actions are reduced
+const LMR_MIN_INDEX: usize = 3;
+/// Index (in the sorted actions) from which quiet actions are reduced by an additional ply
+const LMR_DEEP_INDEX: usize = 8;
+
+/// Maximum number of threat extensions in a line
+const MAX_THREAT_EXTENSIONS: u64 = 2;
+
+/// Initial half-width of the aspiration window centered on the previous iteration's score
+const ASPIRATION_WINDOW: Score = 64;
+/// Minimum depth at which aspiration windows are used (shallower depths are searched with the full window)
+const ASPIRATION_MIN_DEPTH: u64 = 3;
+
 #[cfg(feature = "nps-count")]
 /// Counts the number of evaluated nodes during a search
 pub static TOTAL_NODE_COUNT: AtomicU64 = AtomicU64::new(0);
@@ -36,10 +62,298 @@ pub fn increment_node_count(node_count: u64) {
     TOTAL_NODE_COUNT.fetch_add(node_count, Relaxed);
 }
 
+/// A limit on the number of nodes of a search, shared by all the threads of the search.
+pub struct NodeLimit {
+    /// The maximum number of nodes
+    max_nodes: u64,
+    /// The number of nodes searched so far
+    nodes: AtomicU64,
+}
+
+impl NodeLimit {
+    /// Creates a new limit of the chosen number of nodes.
+    pub fn new(max_nodes: u64) -> Self {
+        Self {
+            max_nodes,
+            nodes: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a searched node.
+    #[inline(always)]
+    pub fn count(&self) {
+        self.nodes.fetch_add(1, Relaxed);
+    }
+
+    /// Returns whether the maximum number of nodes has been searched.
+    #[inline]
+    pub fn is_reached(&self) -> bool {
+        self.nodes.load(Relaxed) >= self.max_nodes
+    }
+}
+
+/// The parameters shared by all the nodes of a search.
+#[derive(Clone, Copy)]
+pub struct SearchContext<'a> {
+    /// The time after which the search stops (if there are time controls)
+    pub end_time: Option<Instant>,
+    /// The transposition table used to store previously searched positions (optional)
+    pub transposition_table: Option<&'a RwLock<SearchTable>>,
+    /// The weights of the evaluation terms
+    pub eval_weights: &'a EvalWeights,
+    /// The network that replaces the piece-square tables (optional)
+    #[cfg(feature = "nnue")]
+    pub network: Option<&'a Network>,
+    /// The killer moves and history used to sort quiet actions (optional)
+    pub heuristics: Option<&'a SearchHeuristics>,
+    /// Using late move reductions
+    pub use_lmr: bool,
+    /// Using null-move pruning
+    pub use_null_move: bool,
+    /// Using threat extensions
+    pub use_threat_extensions: bool,
+    /// Searching the sibling actions in parallel
+    ///
+    /// Otherwise, the search is sequential and deterministic: a given position, depth and transposition table state always yield the same result.
+    pub parallel: bool,
+    /// The flag that stops the search when set (optional)
+    pub stop: Option<&'a AtomicBool>,
+    /// The limit on the number of nodes that stops the search when reached (optional)
+    pub node_limit: Option<&'a NodeLimit>,
+    /// The contempt of the player at the root: a draw is scored `-contempt` from their point of view (positive values avoid draws)
+    pub contempt: Score,
+    /// The half move counter of the root position
+    pub half_moves: u64,
+    /// The hashes of the positions of the game before the root position since the last capture (oldest first)
+    pub history: &'a [usize],
+    /// The statistics collected during the search (optional)
+    pub stats: Option<&'a SearchStats>,
+    /// The callback that receives the information events of the search (optional, the search is silent otherwise)
+    pub info: Option<&'a InfoCallback<'a>>,
+}
+
+impl<'a> SearchContext<'a> {
+    /// Creates a new `SearchContext` that evaluates positions with the piece-square tables.
+    ///
+    /// Late move reductions, null-move pruning and threat extensions are enabled. The sibling actions are searched in parallel.
+    ///
+    /// The root position has no previous positions and draws are scored 0. No statistics are collected and no information is reported.
+    pub fn new(
+        end_time: Option<Instant>,
+        transposition_table: Option<&'a RwLock<SearchTable>>,
+        eval_weights: &'a EvalWeights,
+    ) -> Self {
+        Self {
+            end_time,
+            transposition_table,
+            eval_weights,
+            #[cfg(feature = "nnue")]
+            network: None,
+            heuristics: None,
+            use_lmr: true,
+            use_null_move: true,
+            use_threat_extensions: true,
+            parallel: true,
+            stop: None,
+            node_limit: None,
+            contempt: 0,
+            half_moves: 0,
+            history: &[],
+            stats: None,
+            info: None,
+        }
+    }
+
+    /// Increments a counter of the statistics (if they are collected).
+    #[inline(always)]
+    pub fn count(&self, counter: impl FnOnce(&SearchStats) -> &AtomicU64) {
+        if let Some(stats) = self.stats {
+            SearchStats::increment(counter(stats));
+        }
+    }
+
+    /// Reports an information event to the info callback (if there is one).
+    #[inline]
+    pub fn report(&self, info: impl FnOnce() -> SearchInfo) {
+        if let Some(callback) = self.info {
+            callback(info());
+        }
+    }
+
+    /// Returns whether the search must stop (the allocated time is up, the stop flag is set or the node limit is reached).
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.end_time
+            .is_some_and(|end_time| Instant::now() > end_time)
+            || self.stop.is_some_and(|stop| stop.load(Relaxed))
+            || self.node_limit.is_some_and(NodeLimit::is_reached)
+    }
+}
+
+/// The way the search is spread over the threads of the thread pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchMode {
+    /// A single iterative deepening search, the sibling actions of each node are searched in parallel
+    #[default]
+    Split,
+    /// Lazy SMP: each thread runs its own sequential iterative deepening search, the threads share the transposition table
+    LazySmp,
+}
+
+impl SearchMode {
+    /// All the search modes
+    pub const ALL: [Self; 2] = [Self::Split, Self::LazySmp];
+
+    /// Returns the name of the search mode (used by the UGI options).
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Split => "split",
+            Self::LazySmp => "lazy-smp",
+        }
+    }
+}
+
+/// The maximum number of previous positions stored in a line (positions before the last capture cannot be repeated)
+const MAX_LINE_HISTORY: usize = MAX_HALF_MOVES as usize;
+
+/// The state of the line being searched (from the root to the current node).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLine {
+    /// The distance from the root (the children of the root are at ply 1)
+    pub ply: u64,
+    /// Whether the last action of the line is a null move
+    pub null_move: bool,
+    /// The number of threat extensions in the line
+    pub extensions: u64,
+    /// The half move counter (number of actions since the last capture)
+    pub half_moves: u64,
+    /// The hashes of the previous positions since the last capture or null move (oldest first)
+    history: [usize; MAX_LINE_HISTORY],
+    /// The number of stored hashes
+    history_len: usize,
+}
+
+impl SearchLine {
+    /// Returns the line at the root of the search, with the half move counter and the hashes of the previous positions of the game since the last capture (oldest first).
+    pub fn root(half_moves: u64, history: &[usize]) -> Self {
+        let history = &history[history.len().saturating_sub(MAX_LINE_HISTORY)..];
+        let mut line = Self {
+            half_moves,
+            history_len: history.len(),
+            ..Self::default()
+        };
+        line.history[..history.len()].copy_from_slice(history);
+        line
+    }
+
+    /// Returns the line after playing an action from the position with the given hash.
+    ///
+    /// Captures reset the half move counter and the previous positions.
+    #[inline]
+    pub fn next(self, cells_hash: usize, capture: bool) -> Self {
+        let mut line = Self {
+            ply: self.ply + 1,
+            null_move: false,
+            ..self
+        };
+        if capture {
+            line.half_moves = 0;
+            line.history_len = 0;
+        } else {
+            line.half_moves += 1;
+            if line.history_len < MAX_LINE_HISTORY {
+                line.history[line.history_len] = cells_hash;
+                line.history_len += 1;
+            }
+        }
+        line
+    }
+
+    /// Returns the line after playing a null move (passing the turn).
+    ///
+    /// The previous positions are forgotten since passing the turn is not a legal action.
+    #[inline]
+    pub fn next_null_move(self) -> Self {
+        Self {
+            ply: self.ply + 1,
+            null_move: true,
+            half_moves: self.half_moves + 1,
+            history_len: 0,
+            ..self
+        }
+    }
+
+    /// Returns the line after extending the search by one ply.
+    #[inline]
+    pub fn extend(self) -> Self {
+        Self {
+            extensions: self.extensions + 1,
+            ..self
+        }
+    }
+
+    /// Returns whether the position with the given hash has already occurred in the line.
+    #[inline]
+    pub fn is_repetition(&self, cells_hash: usize) -> bool {
+        self.history[..self.history_len].contains(&cells_hash)
+    }
+
+    /// Returns the score of a draw from the point of view of the current player, given the contempt of the player at the root of the search.
+    #[inline]
+    pub fn draw_score(&self, contempt: Score) -> Score {
+        // The player at the root plays at even plies
+        if self.ply & 1 == 0 {
+            -contempt
+        } else {
+            contempt
+        }
+    }
+}
+
+/// Converts a score relative to the root of the search to a score relative to the current node before storing it in the transposition table.
+///
+/// Win and loss scores depend on the distance from the root, they are stored as the distance from the current node instead.
+#[inline]
+fn score_to_table(score: Score, ply: u64) -> Score {
+    if score >= MATE_THRESHOLD {
+        score + ply as Score
+    } else if score <= -MATE_THRESHOLD {
+        score - ply as Score
+    } else {
+        score
+    }
+}
+
+/// Converts a score read from the transposition table to a score relative to the root of the search (see [`score_to_table`]).
+#[inline]
+fn score_from_table(score: Score, ply: u64) -> Score {
+    if score >= MATE_THRESHOLD {
+        score - ply as Score
+    } else if score <= -MATE_THRESHOLD {
+        score + ply as Score
+    } else {
+        score
+    }
+}
+
+/// Converts a score to its string representation in the info logs.
+///
+/// Wins and losses are represented as `mate N` where N is the number of actions of the winning side until the end of the game (negative if the current player is losing).
+pub fn score_to_string(score: Score) -> String {
+    match mate_distance(score) {
+        Some(distance) => format!("mate {distance}"),
+        None => score.to_string(),
+    }
+}
+
 /// Reads the transposition table and returns its entry (action, depth, score, node type) if it exists.
+///
+/// The ply is the distance of the current node from the root of the search (used to adjust win and loss scores).
 #[inline]
 pub fn read_transposition_table(
     cells_hash: usize,
+    ply: u64,
     transposition_table: Option<&RwLock<SearchTable>>,
 ) -> Option<(Action, u64, Score, NodeType)> {
     if let Some(transposition_table) = transposition_table {
@@ -47,7 +361,12 @@ pub fn read_transposition_table(
         if let Some((table_depth, table_action, table_score, table_node_type)) =
             transposition_table.read(cells_hash)
         {
-            return Some((table_action, table_depth, table_score, table_node_type));
+            return Some((
+                table_action,
+                table_depth,
+                score_from_table(table_score, ply),
+                table_node_type,
+            ));
         }
     }
     None
@@ -56,6 +375,8 @@ pub fn read_transposition_table(
 /// Write the transposition table and store an entry (action, depth, score, node type).
 ///
 /// Replaces the stored entry if the new entry has a higher depth or is the same depth and is a PV node.
+///
+/// The ply is the distance of the current node from the root of the search (used to adjust win and loss scores).
 #[inline]
 pub fn write_transposition_table(
     cells_hash: usize,
@@ -63,21 +384,40 @@ pub fn write_transposition_table(
     depth: u64,
     score: Score,
     node_type: NodeType,
+    ply: u64,
     transposition_table: Option<&RwLock<SearchTable>>,
 ) {
     if let Some(transposition_table) = transposition_table {
         let mut transposition_table = transposition_table.write().unwrap();
-        transposition_table.insert(cells_hash, depth, action, score, node_type);
+        transposition_table.insert(
+            cells_hash,
+            depth,
+            action,
+            score_to_table(score, ply),
+            node_type,
+        );
     }
 }
 
+/// Returns whether an action captures at least one piece.
+#[inline]
+fn is_capture(board: &Board, current_player: Player, action: Action) -> bool {
+    let capturable = board.capturable(current_player);
+    let (_index_start, index_mid, index_end) = action.to_indices();
+    (!index_mid.is_null() && capturable.get(index_mid)) || capturable.get(index_end)
+}
+
 /// Sorts the available actions based on how good they are estimated to be (in descending order -> best actions first).
+///
+/// The TT action is searched first, then the captures, then the killer moves and the other quiet actions sorted by history (if the heuristics are available).
 #[inline]
 fn sort_actions(
     board: &Board,
     current_player: Player,
     table_action: Option<Action>,
     available_actions: &mut Actions,
+    ply: u64,
+    heuristics: Option<&SearchHeuristics>,
 ) -> Option<Action> {
     let n_actions = available_actions.len();
     let mut index_sorted = 0;
@@ -99,6 +439,7 @@ fn sort_actions(
 
     // Skip sorting the first action if there is a TT action
     let index_start = index_sorted;
+    let capturable = board.capturable(current_player);
     // Find all the captures and put them at the beginning
     for i in index_start..n_actions {
         let action = available_actions[i];
@@ -107,34 +448,57 @@ fn sort_actions(
             return Some(action);
         }
         let (_index_start, index_mid, index_end) = action.to_indices();
-        if (!index_mid.is_null() && board.capturable(current_player).get(index_mid))
-            || (board.capturable(current_player).get(index_end))
-        {
+        if (!index_mid.is_null() && capturable.get(index_mid)) || (capturable.get(index_end)) {
             available_actions[i] = available_actions[index_sorted];
             available_actions[index_sorted] = action;
             index_sorted += 1;
         }
     }
+
+    if let Some(heuristics) = heuristics {
+        // Put the killer moves right after the captures
+        for killer in heuristics.killers.get(ply) {
+            for i in index_sorted..n_actions {
+                if available_actions[i] == killer {
+                    available_actions[..].swap(index_sorted, i);
+                    index_sorted += 1;
+                    break;
+                }
+            }
+        }
+        // Sort the remaining quiet actions by decreasing history
+        // The history values are read once before sorting since other threads may update them concurrently
+        let quiet_actions = &mut available_actions[index_sorted..n_actions];
+        let mut keys = [0u64; MAX_PLAYER_ACTIONS];
+        let keys = &mut keys[..quiet_actions.len()];
+        for (key, &action) in keys.iter_mut().zip(quiet_actions.iter()) {
+            *key = ((heuristics.history.get(action) as u64) << 32) | action as u64;
+        }
+        keys.sort_unstable_by(|a, b| b.cmp(a));
+        for (action, &key) in quiet_actions.iter_mut().zip(keys.iter()) {
+            *action = key as Action;
+        }
+    }
     None
 }
 
-/// Returns the best move at a given depth
+/// Returns the best move at a given depth, searched with the given (alpha, beta) window.
+///
+/// If the returned score is lower than alpha or higher than beta, it is only an upper bound or a lower bound of the actual score.
 pub fn search_root(
     board: &Board,
     current_player: Player,
     depth: u64,
-    end_time: Option<Instant>,
     scores: &Option<Vec<Score>>,
-    transposition_table: Option<&RwLock<SearchTable>>,
+    (alpha, beta): (Score, Score),
+    context: &SearchContext,
 ) -> Option<(Action, Score, Vec<Score>)> {
     if depth == 0 {
         return None;
     }
 
-    if let Some(end_time) = end_time {
-        if Instant::now() > end_time {
-            return None;
-        }
+    if context.is_stopped() {
+        return None;
     }
 
     // Get an array of all the available moves for the current player, the last element of the array is the number of available moves
@@ -151,31 +515,28 @@ pub fn search_root(
     }
 
     let scores: Vec<Score> = {
-        // Cutoffs will happen on winning actions
-        let alpha = BASE_ALPHA;
-        let beta = BASE_BETA;
-
         let mut scores: Vec<Score> = vec![-MAX_SCORE; n_actions];
 
-        let static_eval = evaluate_position(board);
+        let static_eval = StaticEval::new(board, context);
+        let root_line = SearchLine::root(context.half_moves, context.history);
+        let root_hash = (board, current_player).hash();
 
         let first_action = available_actions[order[0]];
         let first_eval = if board.is_action_win(first_action, current_player) {
-            MAX_SCORE
+            win_score(1)
         } else {
             // Principal Variation Search: search the first move with the full window, search subsequent moves with a null window first then if they fail high, search them with a full window
             let mut new_board = *board;
             new_board.play_action(first_action);
-            let new_static_eval =
-                evaluate_position_incremental(board, &new_board, first_action, static_eval);
+            let new_static_eval = static_eval.update(board, &new_board, first_action, context);
             -search_node(
                 (&new_board, 1 - current_player),
                 depth - 1,
+                root_line.next(root_hash, new_board.count_pieces() != board.count_pieces()),
                 (-beta, -alpha),
-                end_time,
                 NodeType::PV,
-                transposition_table,
                 new_static_eval,
+                context,
             )
         };
         scores[0] = first_eval;
@@ -185,72 +546,68 @@ pub fn search_root(
         let atomic_cut: AtomicBool = AtomicBool::new(alpha_atomic.load(Relaxed) > beta);
 
         // Evaluate possible moves
-        scores
-            .iter_mut()
-            .enumerate()
-            .skip(1)
-            .par_bridge()
-            .for_each(|(k, score)| {
-                *score = {
-                    if atomic_cut.load(Relaxed) {
-                        Score::MIN
+        let search_action = |(k, score): (usize, &mut Score)| {
+            *score = {
+                let action = available_actions[order[k]];
+                // Immediate wins are always scored so that a cutoff on a longer win does not hide them
+                if board.is_action_win(action, current_player) {
+                    win_score(1)
+                } else if atomic_cut.load(Relaxed) {
+                    Score::MIN
+                } else {
+                    let mut new_board = *board;
+                    new_board.play_action(action);
+                    let new_static_eval = static_eval.update(board, &new_board, action, context);
+                    let new_line =
+                        root_line.next(root_hash, new_board.count_pieces() != board.count_pieces());
+                    let alpha = alpha_atomic.load(Relaxed);
+                    // Search with a null window
+                    let eval_null_window = -search_node(
+                        (&new_board, 1 - current_player),
+                        depth - 1,
+                        new_line,
+                        (-alpha - 1, -alpha),
+                        NodeType::Cut,
+                        new_static_eval,
+                        context,
+                    );
+                    // If fail high, do the search with the full window
+                    let eval = if alpha < eval_null_window && eval_null_window < beta {
+                        context.count(|stats| &stats.pvs_researches);
+                        -search_node(
+                            (&new_board, 1 - current_player),
+                            depth - 1,
+                            new_line,
+                            (-beta, -alpha),
+                            NodeType::PV,
+                            new_static_eval,
+                            context,
+                        )
                     } else {
-                        let action = available_actions[order[k]];
-                        let eval = if board.is_action_win(action, current_player) {
-                            MAX_SCORE
-                        } else {
-                            let mut new_board = *board;
-                            new_board.play_action(action);
-                            let new_static_eval = evaluate_position_incremental(
-                                board,
-                                &new_board,
-                                action,
-                                static_eval,
-                            );
-                            let alpha = alpha_atomic.load(Relaxed);
-                            // Search with a null window
-                            let eval_null_window = -search_node(
-                                (&new_board, 1 - current_player),
-                                depth - 1,
-                                (-alpha - 1, -alpha),
-                                end_time,
-                                NodeType::Cut,
-                                transposition_table,
-                                new_static_eval,
-                            );
-                            // If fail high, do the search with the full window
-                            if alpha < eval_null_window && eval_null_window < beta {
-                                -search_node(
-                                    (&new_board, 1 - current_player),
-                                    depth - 1,
-                                    (-beta, -alpha),
-                                    end_time,
-                                    NodeType::PV,
-                                    transposition_table,
-                                    new_static_eval,
-                                )
-                            } else {
-                                eval_null_window
-                            }
-                        };
-
-                        alpha_atomic.fetch_max(eval, Relaxed);
-
-                        // Cutoff
-                        if eval > beta {
-                            atomic_cut.store(true, Relaxed);
-                        }
-                        eval
+                        eval_null_window
+                    };
+
+                    alpha_atomic.fetch_max(eval, Relaxed);
+
+                    // Cutoff
+                    if eval > beta {
+                        atomic_cut.store(true, Relaxed);
                     }
+                    eval
                 }
-            });
+            }
+        };
+        let actions = scores.iter_mut().enumerate().skip(1);
+        if context.parallel {
+            actions.par_bridge().for_each(search_action);
+        } else {
+            actions.for_each(search_action);
+        }
         scores
     };
 
-    if let Some(end_time) = end_time {
-        if Instant::now() > end_time {
-            return None;
-        }
+    if context.is_stopped() {
+        return None;
     }
 
     let scores: Vec<Score> = reverse_argsort(&scores, &order);
@@ -273,24 +630,53 @@ pub fn search_root(
 /// Evaluates the score of a given action by searching at a given depth.
 ///
 /// Recursively calculates the best score using the alphabeta search to the chosen depth.
+///
+/// The line contains the distance of the node from the root of the search, whether it was reached by a null move and the number of extensions.
 pub fn search_node(
     (board, current_player): (&Board, Player),
     depth: u64,
+    line: SearchLine,
     (alpha, beta): (Score, Score),
-    end_time: Option<Instant>,
     node_type: NodeType,
-    transposition_table: Option<&RwLock<SearchTable>>,
-    static_eval: Score,
+    static_eval: StaticEval,
+    context: &SearchContext,
 ) -> Score {
+    // Draw by the half move limit
+    if line.half_moves >= MAX_HALF_MOVES {
+        return line.draw_score(context.contempt);
+    }
+
+    // Threat extension: at the edge of the search, search one more ply if the opponent threatens to win immediately instead of evaluating the position
+    // This covers the threats created by the previous action as well, the number of extensions is capped per line
+    let (depth, line) = if depth == 0
+        && context.use_threat_extensions
+        && line.extensions < MAX_THREAT_EXTENSIONS
+        && board.has_winning_action(1 - current_player)
+    {
+        (depth + 1, line.extend())
+    } else {
+        (depth, line)
+    };
+
     if depth == 0 {
-        return quiescence_search(board, current_player, (alpha, beta), static_eval);
+        return quiescence_search(
+            board,
+            current_player,
+            line.ply,
+            (alpha, beta),
+            static_eval,
+            context,
+        );
     }
 
-    // Stop searching if the allocated time is up (if there are time controls)
-    if let Some(end_time) = end_time {
-        if Instant::now() > end_time {
-            return -MAX_SCORE;
-        }
+    // Stop searching if the allocated time is up (if there are time controls), if the node limit is reached or if the search is stopped
+    if context.is_stopped() {
+        return -MAX_SCORE;
+    }
+
+    context.count(|stats| &stats.nodes);
+    if let Some(node_limit) = context.node_limit {
+        node_limit.count();
     }
 
     let mut available_actions = board.available_player_actions(current_player);
@@ -298,7 +684,13 @@ pub fn search_node(
 
     // If there are no actions available, the player has lost
     if n_actions == 0 {
-        return -MAX_SCORE;
+        return -win_score(line.ply);
+    }
+
+    // Draw by repetition (only detected before the edge of the search, the leaves are not hashed)
+    let cells_hash = (board, current_player).hash();
+    if line.is_repetition(cells_hash) {
+        return line.draw_score(context.contempt);
     }
 
     let mut score = -MAX_SCORE;
@@ -306,34 +698,81 @@ pub fn search_node(
     let mut alpha = alpha;
     let mut beta = beta;
     // Read the transposition table
-    let cells_hash = (board, current_player).hash();
-    let table_action = match read_transposition_table(cells_hash, transposition_table) {
-        Some((table_action, table_depth, table_score, table_node_type)) => {
-            // If the table has a match with the same depth, a cutoff may be possible depending on the node type
-            if table_depth == depth {
-                match table_node_type {
-                    NodeType::PV => return table_score,
-                    NodeType::Cut => {
-                        if table_score > beta {
-                            return table_score;
+    if context.transposition_table.is_some() {
+        context.count(|stats| &stats.tt_probes);
+    }
+    let table_action =
+        match read_transposition_table(cells_hash, line.ply, context.transposition_table) {
+            Some((table_action, table_depth, table_score, table_node_type)) => {
+                context.count(|stats| &stats.tt_hits);
+                // If the table has a match with the same depth, a cutoff may be possible depending on the node type
+                if table_depth == depth {
+                    let is_cutoff = match table_node_type {
+                        NodeType::PV => true,
+                        NodeType::Cut => {
+                            alpha = table_score;
+                            table_score > beta
                         }
-                        alpha = table_score;
-                    }
-                    NodeType::All => {
-                        if table_score < alpha {
-                            return table_score;
+                        NodeType::All => {
+                            beta = table_score;
+                            table_score < alpha
                         }
-                        beta = table_score;
+                    };
+                    if is_cutoff {
+                        context.count(|stats| &stats.tt_cutoffs);
+                        return table_score;
                     }
                 }
+                Some(table_action)
+            }
+            None => None,
+        };
+
+    // Null-move pruning: if passing the turn still fails high with a reduced search, the position is assumed to fail high
+    // Skipped when the current player has few non-wise pieces left since passing the turn could be better than any action (zugzwang)
+    if context.use_null_move
+        && node_type != NodeType::PV
+        && !line.null_move
+        && depth >= NULL_MOVE_MIN_DEPTH
+        && beta < BASE_BETA
+        && board.same_colour_not_wise(current_player).0.count_ones() >= NULL_MOVE_MIN_PIECES
+    {
+        let leaf_eval = static_eval.evaluate(board, context);
+        let stand_pat = if current_player == 0 {
+            leaf_eval
+        } else {
+            -leaf_eval
+        };
+        if stand_pat > beta {
+            let null_move_eval = -search_node(
+                (board, 1 - current_player),
+                depth - 1 - NULL_MOVE_REDUCTION,
+                line.next_null_move(),
+                (-beta - 1, -beta),
+                NodeType::All,
+                static_eval,
+                context,
+            );
+            // The result of a stopped search is not reliable
+            if context.is_stopped() {
+                return -MAX_SCORE;
+            }
+            if null_move_eval > beta {
+                // Do not return unproven wins
+                return min(null_move_eval, BASE_BETA);
             }
-            Some(table_action)
         }
-        None => None,
-    };
+    }
 
     // Sort actions to improve alphabeta search
-    let winning_action = sort_actions(board, current_player, table_action, &mut available_actions);
+    let winning_action = sort_actions(
+        board,
+        current_player,
+        table_action,
+        &mut available_actions,
+        line.ply,
+        context.heuristics,
+    );
 
     // Return if one of the available actions is an immediate win
     if let Some(winning_action) = winning_action {
@@ -341,11 +780,12 @@ pub fn search_node(
             cells_hash,
             winning_action,
             depth,
-            MAX_SCORE,
+            win_score(line.ply + 1),
             NodeType::PV,
-            transposition_table,
+            line.ply,
+            context.transposition_table,
         );
-        return MAX_SCORE;
+        return win_score(line.ply + 1);
     }
 
     // Principal Variation Search: search the first move with the full window, search subsequent moves with a null window first then if they fail high, search them with a full window
@@ -353,31 +793,42 @@ pub fn search_node(
     let mut new_board = *board;
     let first_action = available_actions[0];
     new_board.play_action(first_action);
-    let new_static_eval =
-        evaluate_position_incremental(board, &new_board, first_action, static_eval);
+    let new_static_eval = static_eval.update(board, &new_board, first_action, context);
     let eval = -search_node(
         (&new_board, 1 - current_player),
         depth - 1,
+        line.next(cells_hash, new_board.count_pieces() != board.count_pieces()),
         (-beta, -alpha),
-        end_time,
         match node_type {
             NodeType::PV => NodeType::PV,
             NodeType::Cut => NodeType::All,
             NodeType::All => NodeType::Cut,
         },
-        transposition_table,
         new_static_eval,
+        context,
     );
+    // The scores of a stopped search are not reliable, they must not be stored in the transposition table or in the heuristics
+    if context.is_stopped() {
+        return -MAX_SCORE;
+    }
     alpha = max(alpha, eval);
     // Beta-cutoff, stop the search
     if alpha > beta {
+        context.count(|stats| &stats.beta_cutoffs);
+        context.count(|stats| &stats.first_move_cutoffs);
+        if let Some(heuristics) = context.heuristics {
+            if !is_capture(board, current_player, first_action) {
+                heuristics.update(first_action, depth, line.ply);
+            }
+        }
         write_transposition_table(
             cells_hash,
             available_actions[0],
             depth,
             eval,
             node_type,
-            transposition_table,
+            line.ply,
+            context.transposition_table,
         );
         return eval;
     }
@@ -390,65 +841,105 @@ pub fn search_node(
     // This will stop iteration if there is a cutoff
     let cut_atomic = AtomicBool::new(false);
 
-    // Evaluate the rest of the actions in parallel
-    available_actions
-        .into_iter()
-        .skip(1)
-        .par_bridge()
-        .for_each(|action| {
-            if !cut_atomic.load(Relaxed) {
-                let eval = {
-                    let alpha = alpha_atomic.load(Relaxed);
+    // Evaluate the rest of the actions (in parallel if enabled)
+    let search_action = |(index, action): (usize, Action)| {
+        if !cut_atomic.load(Relaxed) {
+            let eval = {
+                let alpha = alpha_atomic.load(Relaxed);
 
-                    let mut new_board = *board;
-                    new_board.play_action(action);
-                    let new_static_eval =
-                        evaluate_position_incremental(board, &new_board, action, static_eval);
-                    // Search with a null window
-                    let eval_null_window = -search_node(
+                let mut new_board = *board;
+                new_board.play_action(action);
+                let new_static_eval = static_eval.update(board, &new_board, action, context);
+                let new_line =
+                    line.next(cells_hash, new_board.count_pieces() != board.count_pieces());
+                // Late move reductions: quiet actions sorted late are searched at a reduced depth first
+                let reduction = if context.use_lmr
+                    && depth >= LMR_MIN_DEPTH
+                    && index >= LMR_MIN_INDEX
+                    && !is_capture(board, current_player, action)
+                {
+                    if index >= LMR_DEEP_INDEX && depth > LMR_MIN_DEPTH {
+                        2
+                    } else {
+                        1
+                    }
+                } else {
+                    0
+                };
+                let null_window_node_type = match node_type {
+                    NodeType::PV => NodeType::Cut,
+                    NodeType::Cut => NodeType::Cut,
+                    NodeType::All => NodeType::Cut,
+                };
+                // Search with a null window
+                let mut eval_null_window = -search_node(
+                    (&new_board, 1 - current_player),
+                    depth - 1 - reduction,
+                    new_line,
+                    (-alpha - 1, -alpha),
+                    null_window_node_type,
+                    new_static_eval,
+                    context,
+                );
+                // If the reduced search fails high, search again at full depth
+                if reduction > 0 && eval_null_window > alpha {
+                    eval_null_window = -search_node(
                         (&new_board, 1 - current_player),
                         depth - 1,
+                        new_line,
                         (-alpha - 1, -alpha),
-                        end_time,
+                        null_window_node_type,
+                        new_static_eval,
+                        context,
+                    );
+                }
+
+                // If fail high, do the search with the full window
+                if alpha < eval_null_window && eval_null_window < beta {
+                    context.count(|stats| &stats.pvs_researches);
+                    -search_node(
+                        (&new_board, 1 - current_player),
+                        depth - 1,
+                        new_line,
+                        (-beta, -alpha),
                         match node_type {
-                            NodeType::PV => NodeType::Cut,
+                            NodeType::PV => NodeType::PV,
                             NodeType::Cut => NodeType::Cut,
                             NodeType::All => NodeType::Cut,
                         },
-                        transposition_table,
                         new_static_eval,
-                    );
-
-                    // If fail high, do the search with the full window
-                    if alpha < eval_null_window && eval_null_window < beta {
-                        -search_node(
-                            (&new_board, 1 - current_player),
-                            depth - 1,
-                            (-beta, -alpha),
-                            end_time,
-                            match node_type {
-                                NodeType::PV => NodeType::PV,
-                                NodeType::Cut => NodeType::Cut,
-                                NodeType::All => NodeType::Cut,
-                            },
-                            transposition_table,
-                            new_static_eval,
-                        )
-                    } else {
-                        eval_null_window
-                    }
-                };
-                if eval > score_atomic.load(Relaxed) {
-                    score_atomic.store(eval, Relaxed);
-                    best_action_atomic.store(action, Relaxed);
+                        context,
+                    )
+                } else {
+                    eval_null_window
                 }
-                alpha_atomic.fetch_max(eval, Relaxed);
-                // Beta-cutoff, stop the search
-                if eval > beta {
-                    cut_atomic.store(true, Relaxed);
+            };
+            if eval > score_atomic.load(Relaxed) {
+                score_atomic.store(eval, Relaxed);
+                best_action_atomic.store(action, Relaxed);
+            }
+            alpha_atomic.fetch_max(eval, Relaxed);
+            // Beta-cutoff, stop the search
+            if eval > beta {
+                context.count(|stats| &stats.beta_cutoffs);
+                cut_atomic.store(true, Relaxed);
+                if let Some(heuristics) = context.heuristics {
+                    if !is_capture(board, current_player, action) && !context.is_stopped() {
+                        heuristics.update(action, depth, line.ply);
+                    }
                 }
             }
-        });
+        }
+    };
+    let actions = available_actions.into_iter().enumerate().skip(1);
+    if context.parallel {
+        actions.par_bridge().for_each(search_action);
+    } else {
+        actions.for_each(search_action);
+    }
+    if context.is_stopped() {
+        return -MAX_SCORE;
+    }
     score = score_atomic.load(Relaxed);
     write_transposition_table(
         cells_hash,
@@ -456,7 +947,8 @@ pub fn search_node(
         depth,
         score,
         node_type,
-        transposition_table,
+        line.ply,
+        context.transposition_table,
     );
     score
 }
@@ -465,56 +957,113 @@ pub fn search_node(
 ///
 /// The search starts at depth 1 and the depth increases until the chosen depth is reached or a winning move is found.
 /// The results at lower depths are used to sort the search order at higher depths.
+///
+/// The result of each iteration is reported to the info callback of the context (see [`SearchInfo::Iteration`]).
 pub fn search_iterative(
     board: &Board,
     current_player: Player,
     max_depth: u64,
-    end_time: Option<Instant>,
-    verbose: bool,
-    transposition_table: Option<&RwLock<SearchTable>>,
+    context: &SearchContext,
+) -> Option<(Action, Score)> {
+    search_iterative_from(board, current_player, (1, max_depth), context)
+}
+
+/// Returns the best move by searching from the chosen start depth up to the chosen maximum depth (see [`search_iterative`]).
+fn search_iterative_from(
+    board: &Board,
+    current_player: Player,
+    (start_depth, max_depth): (u64, u64),
+    context: &SearchContext,
 ) -> Option<(Action, Score)> {
     let mut best_result: Option<(Action, Score)> = None;
     let mut last_scores: Option<Vec<Score>> = None;
     let start_time = Instant::now();
-    for depth in 1..=max_depth {
-        if let Some(end_time) = end_time {
-            if Instant::now() > end_time {
-                break;
-            }
+    // The move ordering heuristics are kept between iterations
+    let heuristics = SearchHeuristics::default();
+    let context = &SearchContext {
+        heuristics: Some(&heuristics),
+        ..*context
+    };
+    for depth in start_depth..=max_depth {
+        if context.is_stopped() {
+            break;
         }
-        let proposed_action = search_root(
-            board,
-            current_player,
-            depth,
-            end_time,
-            &last_scores,
-            transposition_table,
-        );
-        let duration = start_time.elapsed();
-        let duration_ms: u128 = duration.as_millis();
+        // Aspiration windows: search with a narrow window centered on the previous score, widen it and search again on fail-low or fail-high
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = match best_result {
+            Some((_action, last_score))
+                if depth >= ASPIRATION_MIN_DEPTH
+                    && BASE_ALPHA < last_score
+                    && last_score < BASE_BETA =>
+            {
+                (
+                    max(last_score.saturating_sub(delta), BASE_ALPHA),
+                    min(last_score.saturating_add(delta), BASE_BETA),
+                )
+            }
+            _ => (BASE_ALPHA, BASE_BETA),
+        };
+        let proposed_action = loop {
+            let proposed_action = search_root(
+                board,
+                current_player,
+                depth,
+                &last_scores,
+                (alpha, beta),
+                context,
+            );
+            let Some((action, score, scores)) = proposed_action else {
+                break None;
+            };
+            let bound = if score <= alpha && alpha > BASE_ALPHA {
+                // Losses are searched with the full window immediately
+                alpha = if score <= -MATE_THRESHOLD {
+                    BASE_ALPHA
+                } else {
+                    max(alpha.saturating_sub(delta), BASE_ALPHA)
+                };
+                ScoreBound::Upper
+            } else if score >= beta && beta < BASE_BETA {
+                // Wins are searched with the full window immediately
+                beta = if score >= MATE_THRESHOLD {
+                    BASE_BETA
+                } else {
+                    min(beta.saturating_add(delta), BASE_BETA)
+                };
+                ScoreBound::Lower
+            } else {
+                break Some((action, score, scores));
+            };
+            context.report(|| SearchInfo::Iteration {
+                depth,
+                time: start_time.elapsed(),
+                score,
+                bound: Some(bound),
+                action,
+                nodes: context.stats.map(SearchStats::total_nodes),
+            });
+            delta = delta.saturating_mul(2);
+            last_scores = Some(scores);
+        };
         match proposed_action {
             None => (),
             Some((action, score, scores)) => {
-                let action_string = action_to_string(board, action);
-                if verbose {
-                    print!(
-                        "info depth {depth} time {duration_ms} score {score} pv {action_string}"
-                    );
-                    #[cfg(feature = "nps-count")]
-                    print!(
-                        " nodes {} nps {}",
-                        TOTAL_NODE_COUNT.load(Relaxed),
-                        TOTAL_NODE_COUNT.load(Relaxed) as u128 * 1_000_000_000
-                            / duration.as_nanos()
-                    );
-                    println!();
+                if let Some(stats) = context.stats {
+                    stats.end_iteration(depth);
                 }
                 #[cfg(feature = "nps-count")]
-                TOTAL_NODE_COUNT.store(0, Relaxed);
+                let nodes = Some(TOTAL_NODE_COUNT.swap(0, Relaxed));
+                #[cfg(not(feature = "nps-count"))]
+                let nodes = context.stats.map(SearchStats::total_nodes);
+                context.report(|| SearchInfo::Iteration {
+                    depth,
+                    time: start_time.elapsed(),
+                    score,
+                    bound: None,
+                    action,
+                    nodes,
+                });
                 if score < BASE_ALPHA {
-                    if verbose {
-                        println!("info loss in {}", min(1, depth / 2));
-                    }
                     best_result = if let Some((last_action, _last_score)) = best_result {
                         Some((last_action, score))
                     } else {
@@ -525,13 +1074,6 @@ pub fn search_iterative(
                 best_result = Some((action, score));
                 last_scores = Some(scores);
                 if score > BASE_BETA {
-                    if verbose {
-                        if depth > 1 {
-                            println!("info mate in {}", depth / 2);
-                        } else {
-                            println!("info mate");
-                        }
-                    }
                     break;
                 }
             }
@@ -539,3 +1081,38 @@ pub fn search_iterative(
     }
     best_result
 }
+
+/// Returns the best move by searching up to the chosen depth with the lazy SMP algorithm.
+///
+/// Each thread of the thread pool runs its own sequential iterative deepening search (see [`search_iterative`]) and the threads share the transposition table.
+/// The helper threads fill the transposition table, the result of the main thread is returned (and reported) and the helper threads are stopped when it finishes.
+/// Half of the helper threads start one ply deeper so that the threads do not search the same depths at the same time.
+pub fn search_lazy_smp(
+    board: &Board,
+    current_player: Player,
+    max_depth: u64,
+    context: &SearchContext,
+) -> Option<(Action, Score)> {
+    let stop = AtomicBool::new(false);
+    let context = &SearchContext {
+        parallel: false,
+        stop: Some(&stop),
+        ..*context
+    };
+    rayon::scope(|scope| {
+        for thread_index in 1..rayon::current_num_threads() {
+            scope.spawn(move |_| {
+                // The helper threads do not report their results
+                let context = &SearchContext {
+                    info: None,
+                    ..*context
+                };
+                let start_depth = 1 + (thread_index & 1) as u64;
+                search_iterative_from(board, current_player, (start_depth, u64::MAX), context);
+            });
+        }
+        let result = search_iterative(board, current_player, max_depth, context);
+        stop.store(true, Relaxed);
+        result
+    })
+}
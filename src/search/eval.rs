@@ -1,21 +1,150 @@
 //! This module implements the evaluation functions: evaluates the score of a current position or evaluates the best score at a given depth.
 
-use std::cmp::max;
+use std::cmp::{max, min};
 
-use crate::bitboard::Board;
+use serde::{Deserialize, Serialize};
+
+use crate::bitboard::{Bitboard, Board};
 use crate::logic::actions::{Action, ActionTrait, ActionsLight};
 use crate::logic::index::{CellIndex, CellIndexTrait};
-use crate::logic::lookup::PIECE_TO_INDEX;
+use crate::logic::lookup::{NEIGHBOURS1, PIECE_TO_INDEX};
+use crate::logic::translate::{cells_to_pretty_string, index_to_coords, piece_to_char};
 use crate::logic::{Player, N_CELLS};
-use crate::piece::Piece;
+use crate::piece::{Piece, PieceTrait};
 use crate::search::lookup::PIECE_SCORES;
 
 #[cfg(feature = "nps-count")]
 use super::alphabeta::increment_node_count;
+use super::alphabeta::SearchContext;
+#[cfg(feature = "nnue")]
+use super::nnue::Accumulator;
 use super::Score;
 
 /// The max score (is reached on winning position)
 pub const MAX_SCORE: Score = 16_384;
+/// The maximum number of plies that can be encoded in a win or loss score
+const MAX_MATE_PLY: u64 = 1_024;
+/// Scores above this value are wins at a known distance (and scores below its opposite are losses)
+pub const MATE_THRESHOLD: Score = MAX_SCORE - MAX_MATE_PLY as Score;
+
+/// Returns the score of a win reached at a given ply from the root of the search.
+///
+/// Shorter wins have higher scores. A loss reached at a given ply has the opposite score.
+#[inline]
+pub fn win_score(ply: u64) -> Score {
+    MAX_SCORE - min(ply, MAX_MATE_PLY) as Score
+}
+
+/// Returns the number of actions of the winning side until the end of the game if the score is a win or a loss.
+///
+/// The distance is positive if the score is a win and negative if it is a loss.
+#[inline]
+pub fn mate_distance(score: Score) -> Option<Score> {
+    if score >= MATE_THRESHOLD {
+        Some((MAX_SCORE - score + 1) / 2)
+    } else if score <= -MATE_THRESHOLD {
+        Some(-((MAX_SCORE + score + 1) / 2))
+    } else {
+        None
+    }
+}
+
+/// The maximum absolute value of the positional terms (see [`evaluate_terms`])
+///
+/// It keeps the positional terms from pushing an evaluation outside of the alphabeta search window.
+const MAX_TERMS_SCORE: i32 = 2_048;
+
+/// The evaluation terms that complement the piece-square tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalTerm {
+    /// Number of legal actions
+    Mobility,
+    /// Number of opponent pieces that can be captured in one action
+    Threats,
+    /// Number of threatened stacks without an allied neighbour (penalty)
+    Unprotected,
+    /// Closeness of the most advanced non-wise piece to the goal row
+    Advancement,
+    /// Number of opponent non-wise pieces adjacent to a wise piece
+    WiseBlocking,
+}
+
+impl EvalTerm {
+    /// All the evaluation terms
+    pub const ALL: [EvalTerm; 5] = [
+        EvalTerm::Mobility,
+        EvalTerm::Threats,
+        EvalTerm::Unprotected,
+        EvalTerm::Advancement,
+        EvalTerm::WiseBlocking,
+    ];
+
+    /// Returns the name of the term, as used by the UGI options.
+    pub const fn name(self) -> &'static str {
+        match self {
+            EvalTerm::Mobility => "mobility",
+            EvalTerm::Threats => "threats",
+            EvalTerm::Unprotected => "unprotected",
+            EvalTerm::Advancement => "advancement",
+            EvalTerm::WiseBlocking => "wise-blocking",
+        }
+    }
+}
+
+/// The weights of the evaluation terms.
+///
+/// A weight set to 0 disables its term. All terms are disabled by default, which makes the evaluation a pure piece-square table sum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvalWeights {
+    /// Weight of [`EvalTerm::Mobility`]
+    pub mobility: Score,
+    /// Weight of [`EvalTerm::Threats`]
+    pub threats: Score,
+    /// Weight of [`EvalTerm::Unprotected`]
+    pub unprotected: Score,
+    /// Weight of [`EvalTerm::Advancement`]
+    pub advancement: Score,
+    /// Weight of [`EvalTerm::WiseBlocking`]
+    pub wise_blocking: Score,
+}
+
+impl EvalWeights {
+    /// Weights with all the terms disabled
+    pub const NONE: Self = Self {
+        mobility: 0,
+        threats: 0,
+        unprotected: 0,
+        advancement: 0,
+        wise_blocking: 0,
+    };
+
+    /// Returns the weight of the chosen term.
+    pub const fn get(&self, term: EvalTerm) -> Score {
+        match term {
+            EvalTerm::Mobility => self.mobility,
+            EvalTerm::Threats => self.threats,
+            EvalTerm::Unprotected => self.unprotected,
+            EvalTerm::Advancement => self.advancement,
+            EvalTerm::WiseBlocking => self.wise_blocking,
+        }
+    }
+
+    /// Sets the weight of the chosen term.
+    pub fn set(&mut self, term: EvalTerm, weight: Score) {
+        match term {
+            EvalTerm::Mobility => self.mobility = weight,
+            EvalTerm::Threats => self.threats = weight,
+            EvalTerm::Unprotected => self.unprotected = weight,
+            EvalTerm::Advancement => self.advancement = weight,
+            EvalTerm::WiseBlocking => self.wise_blocking = weight,
+        }
+    }
+
+    /// Returns true if all the terms are disabled.
+    pub fn is_pst_only(&self) -> bool {
+        EvalTerm::ALL.iter().all(|&term| self.get(term) == 0)
+    }
+}
 
 /// Returns the score of a single cell given its content and index.
 ///
@@ -42,14 +171,19 @@ pub fn evaluate_position(board: &Board) -> Score {
 /// Returns the score of a board from the point of view of the chosen player.
 ///
 /// The higher the score, the better the position.
-pub fn evaluate_position_for_player(board: &Board, current_player: Player) -> Score {
+pub fn evaluate_position_for_player(
+    board: &Board,
+    current_player: Player,
+    weights: &EvalWeights,
+) -> Score {
     #[cfg(feature = "nps-count")]
     increment_node_count(1);
     let eval = board
         .all()
         .into_iter()
         .map(|index| evaluate_cell(board.get_piece(index), index))
-        .sum();
+        .sum::<Score>()
+        + evaluate_terms(board, weights);
     if current_player == 0 {
         eval
     } else {
@@ -57,6 +191,188 @@ pub fn evaluate_position_for_player(board: &Board, current_player: Player) -> Sc
     }
 }
 
+/// Returns a bitboard of the opponent pieces that the given player can capture in one move.
+///
+/// Only the first step of an action is considered (1-range moves for all pieces and 2-range moves for stacks).
+fn threatened(board: &Board, player: Player) -> Bitboard {
+    let mut targets = Bitboard::EMPTY;
+    for index in board.same_colour_not_wise(player) {
+        let piece = board.get_player_piece(index, player);
+        targets = targets | board.available_moves1(index, piece);
+        if piece.is_stack() {
+            targets = targets | board.available_moves2(index, piece);
+        }
+    }
+    targets & board.capturable(player)
+}
+
+/// Returns the raw value of a term for the given player, before weighting.
+///
+/// The higher the value, the better the position for this player.
+pub fn evaluate_term(board: &Board, term: EvalTerm, player: Player) -> Score {
+    let opponent = 1 - player;
+    match term {
+        EvalTerm::Mobility => board.count_player_actions(player) as Score,
+        EvalTerm::Threats => threatened(board, player).0.count_ones() as Score,
+        EvalTerm::Unprotected => {
+            let exposed = threatened(board, opponent) & board.same_stacks(player);
+            -(exposed
+                .filter(|&index| (NEIGHBOURS1[index] & board.same_colour(player)).0 == 0)
+                .count() as Score)
+        }
+        EvalTerm::Advancement => {
+            let pieces = board.same_colour_not_wise(player).0;
+            if pieces == 0 {
+                0
+            } else if player == 0 {
+                let (row, _) = index_to_coords(pieces.trailing_zeros() as CellIndex);
+                6 - row as Score
+            } else {
+                let (row, _) = index_to_coords(63 - pieces.leading_zeros() as CellIndex);
+                row as Score
+            }
+        }
+        EvalTerm::WiseBlocking => {
+            let blocked = board.same_colour_not_wise(opponent);
+            board
+                .same_wise(player)
+                .map(|index| (NEIGHBOURS1[index] & blocked).0.count_ones() as Score)
+                .sum()
+        }
+    }
+}
+
+/// Returns the weighted score of the evaluation terms (see [`EvalTerm`]).
+///
+/// If the score is positive, the position favours the white player.
+/// If the score is negative, the position favours the black player.
+///
+/// These terms are not updated incrementally: the search adds them to the incremental piece-square table score when evaluating the leaves.
+pub fn evaluate_terms(board: &Board, weights: &EvalWeights) -> Score {
+    let score: i32 = EvalTerm::ALL
+        .iter()
+        .filter(|&&term| weights.get(term) != 0)
+        .map(|&term| {
+            weights.get(term) as i32
+                * (evaluate_term(board, term, 0) as i32 - evaluate_term(board, term, 1) as i32)
+        })
+        .sum();
+    score.clamp(-MAX_TERMS_SCORE, MAX_TERMS_SCORE) as Score
+}
+
+/// The detailed contributions of each part of the evaluation of a position.
+///
+/// It traces the piece-square tables and the weighted terms (see [`evaluate_position`] and [`evaluate_terms`]), the network evaluation is not traced.
+///
+/// All the scores are from the point of view of the white player.
+#[derive(Debug, Clone, Copy)]
+pub struct EvalTrace {
+    /// The board that was evaluated
+    pub board: Board,
+    /// The piece-square table score of each cell (0 if the cell is empty)
+    pub cells: [Score; N_CELLS],
+    /// The raw values of each term (see [`EvalTerm::ALL`] for the order) for the white and black players
+    pub terms: [(Score, Score); EvalTerm::ALL.len()],
+    /// The weights of the evaluation terms
+    pub weights: EvalWeights,
+}
+
+impl EvalTrace {
+    /// Evaluates a position and keeps the contribution of each cell and term.
+    pub fn new(board: &Board, weights: &EvalWeights) -> Self {
+        let mut cells = [0; N_CELLS];
+        for index in board.all() {
+            cells[index] = evaluate_cell(board.get_piece(index), index);
+        }
+        let terms = EvalTerm::ALL
+            .map(|term| (evaluate_term(board, term, 0), evaluate_term(board, term, 1)));
+        Self {
+            board: *board,
+            cells,
+            terms,
+            weights: *weights,
+        }
+    }
+
+    /// Returns the piece-square table score of the chosen player's pieces.
+    pub fn pst(&self, player: Player) -> Score {
+        self.board
+            .same_colour(player)
+            .map(|index| self.cells[index])
+            .sum()
+    }
+
+    /// Returns the sum of the weighted terms before it is clamped.
+    pub fn terms_sum(&self) -> i32 {
+        EvalTerm::ALL
+            .iter()
+            .zip(self.terms)
+            .map(|(&term, (white_value, black_value))| {
+                self.weights.get(term) as i32 * (white_value as i32 - black_value as i32)
+            })
+            .sum()
+    }
+
+    /// Returns the total score of the evaluation, equal to [`evaluate_position`] + [`evaluate_terms`].
+    pub fn total(&self) -> Score {
+        self.pst(0) + self.pst(1) + evaluate_terms(&self.board, &self.weights)
+    }
+
+    /// Converts the trace to a pretty formatted string.
+    ///
+    /// The evaluator is named first. The board and the piece-square table scores of each player are drawn using the layout of [`Board::to_pretty_string`] (see [`cells_to_pretty_string`]).
+    /// They are followed by the weighted scores of the other terms, their sum and the clamped sum added to the total.
+    pub fn to_pretty_string(&self) -> String {
+        let board_string = cells_to_pretty_string(5, |index| {
+            let piece = self.board.get_piece(index);
+            if piece.is_empty() {
+                ".".to_owned()
+            } else if piece.is_stack() {
+                format!(
+                    "{}{}",
+                    piece_to_char(piece.top()).unwrap(),
+                    piece_to_char(piece.bottom()).unwrap()
+                )
+            } else {
+                format!("{}-", piece_to_char(piece).unwrap())
+            }
+        });
+        // The score of each cell is drawn on the table of the player owning its piece
+        let pst_string = |player: Player| {
+            cells_to_pretty_string(5, |index| {
+                let piece = self.board.get_piece(index);
+                if !piece.is_empty() && piece.is_white() == (player == 0) {
+                    self.cells[index].to_string()
+                } else {
+                    ".".to_owned()
+                }
+            })
+        };
+
+        let mut trace_string = "evaluator piece-square tables and terms\n".to_owned();
+        trace_string += &format!("board\n{board_string}\n");
+        trace_string += &format!("pst white {}\n{}\n", self.pst(0), pst_string(0));
+        trace_string += &format!("pst black {}\n{}\n", self.pst(1), pst_string(1));
+        for (term, (white_value, black_value)) in EvalTerm::ALL.iter().zip(self.terms) {
+            let weight = self.weights.get(*term);
+            trace_string += &format!(
+                "term {} weight {weight} white {white_value} ({}) black {black_value} ({})\n",
+                term.name(),
+                weight as i32 * white_value as i32,
+                -(weight as i32 * black_value as i32),
+            );
+        }
+        trace_string += &format!(
+            "terms sum {} clamped to [{}, {MAX_TERMS_SCORE}] {}\n",
+            self.terms_sum(),
+            -MAX_TERMS_SCORE,
+            evaluate_terms(&self.board, &self.weights),
+        );
+        trace_string += &format!("total {}", self.total());
+        trace_string
+    }
+}
+
 #[inline]
 /// Evaluates the score of a position after an action.
 ///
@@ -102,6 +418,78 @@ pub fn evaluate_position_incremental(
     score
 }
 
+/// The static evaluation of a position, updated incrementally during the search.
+///
+/// It contains the piece-square table score and, if a network is used, its accumulator (see [`crate::search::nnue`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StaticEval {
+    /// The piece-square table score, from the point of view of the white player
+    pub score: Score,
+    /// The hidden layer of the network
+    #[cfg(feature = "nnue")]
+    pub accumulator: Option<Accumulator>,
+}
+
+impl StaticEval {
+    /// Evaluates a position from scratch.
+    pub fn new(board: &Board, context: &SearchContext) -> Self {
+        #[cfg(not(feature = "nnue"))]
+        let _ = context;
+        Self {
+            score: evaluate_position(board),
+            #[cfg(feature = "nnue")]
+            accumulator: context
+                .network
+                .map(|network| Accumulator::new(network, board)),
+        }
+    }
+
+    /// Returns the static evaluation of a position after an action.
+    ///
+    /// See [`evaluate_position_incremental`].
+    #[inline]
+    pub fn update(
+        &self,
+        old_board: &Board,
+        new_board: &Board,
+        action: Action,
+        context: &SearchContext,
+    ) -> Self {
+        #[cfg(not(feature = "nnue"))]
+        let _ = context;
+        Self {
+            score: evaluate_position_incremental(old_board, new_board, action, self.score),
+            #[cfg(feature = "nnue")]
+            accumulator: self.accumulator.and_then(|mut accumulator| {
+                context.network.map(|network| {
+                    accumulator.update(network, old_board, new_board, action);
+                    accumulator
+                })
+            }),
+        }
+    }
+
+    /// Returns the score of the position, from the point of view of the white player.
+    ///
+    /// The network replaces the piece-square tables if it is used.
+    /// The evaluation terms are not updated incrementally and are computed here.
+    #[inline]
+    pub fn evaluate(&self, board: &Board, context: &SearchContext) -> Score {
+        #[cfg(feature = "nnue")]
+        let score = match (self.accumulator, context.network) {
+            (Some(accumulator), Some(network)) => accumulator.evaluate(network),
+            _ => self.score,
+        };
+        #[cfg(not(feature = "nnue"))]
+        let score = self.score;
+        if context.eval_weights.is_pst_only() {
+            score
+        } else {
+            score + evaluate_terms(board, context.eval_weights)
+        }
+    }
+}
+
 fn sort_captures(
     board: &Board,
     current_player: Player,
@@ -145,20 +533,28 @@ fn sort_captures(
 /// Evaluates a position using quiescence search.
 ///
 /// Resolves all capture chains before evaluating positions and returns the best score using alphabeta.
+///
+/// The ply is the distance of the position from the root of the search (used to score wins).
 pub fn quiescence_search(
     board: &Board,
     current_player: Player,
+    ply: u64,
     (alpha, beta): (Score, Score),
-    static_eval: Score,
+    static_eval: StaticEval,
+    context: &SearchContext,
 ) -> Score {
+    context.count(|stats| &stats.qnodes);
+
     let mut available_captures = board.available_player_captures_and_wins(current_player);
     let n_actions = available_captures.len();
 
+    let leaf_eval = static_eval.evaluate(board, context);
+
     // Heuristic to return early
     let stand_pat = if current_player == 0 {
-        static_eval
+        leaf_eval
     } else {
-        -static_eval
+        -leaf_eval
     };
 
     if n_actions == 0 || stand_pat > beta {
@@ -168,7 +564,7 @@ pub fn quiescence_search(
     let winning_action = sort_captures(board, current_player, &mut available_captures);
 
     if winning_action.is_some() {
-        return MAX_SCORE;
+        return win_score(ply + 1);
     }
 
     let mut score = stand_pat;
@@ -178,18 +574,20 @@ pub fn quiescence_search(
     let mut new_board;
     for action in available_captures.into_iter() {
         if board.is_action_win(action, current_player) {
-            return MAX_SCORE;
+            return win_score(ply + 1);
         }
         new_board = *board;
         new_board.play_action(action);
-        let new_static_eval = evaluate_position_incremental(board, &new_board, action, static_eval);
+        let new_static_eval = static_eval.update(board, &new_board, action, context);
         let eval = max(
             score,
             -quiescence_search(
                 &new_board,
                 1 - current_player,
+                ply + 1,
                 (-beta, -alpha),
                 new_static_eval,
+                context,
             ),
         );
         score = max(score, eval);
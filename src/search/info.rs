@@ -0,0 +1,71 @@
+//! This module implements the typed information events reported during a search.
+//!
+//! The search does not print anything: the events are given to the info callback of the search context (if there is one).
+//! The UGI engine formats them as `info` lines (see [`crate::ugi`]).
+
+use std::time::Duration;
+
+use crate::logic::actions::Action;
+
+use super::stats::StatsSummary;
+use super::Score;
+
+/// The kind of bound of a score found outside of the aspiration window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreBound {
+    /// The search failed high, the score is a lower bound
+    Lower,
+    /// The search failed low, the score is an upper bound
+    Upper,
+}
+
+/// An information event reported during a search.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchInfo {
+    /// The result of an iteration of the iterative deepening (or of an aspiration window search that must be searched again if there is a bound)
+    Iteration {
+        /// The depth of the iteration
+        depth: u64,
+        /// The time elapsed since the start of the search
+        time: Duration,
+        /// The score from the point of view of the current player
+        score: Score,
+        /// The bound of the score if it is not exact
+        bound: Option<ScoreBound>,
+        /// The best action
+        action: Action,
+        /// The number of nodes searched (if they are counted)
+        nodes: Option<u64>,
+    },
+    /// The action found in the opening book
+    Book {
+        /// The depth of the search that computed the book action
+        depth: u64,
+        /// The score of the book action
+        score: Score,
+        /// The book action
+        action: Action,
+    },
+    /// The action chosen at a limited skill level
+    Skill {
+        /// The skill level
+        level: u64,
+        /// The depth of the search
+        depth: u64,
+        /// The score of the chosen action
+        score: Score,
+        /// The chosen action
+        action: Action,
+    },
+    /// The statistics collected during the search (if enabled)
+    Stats(StatsSummary),
+}
+
+/// The callback that receives the information events of a search.
+///
+/// It may be called from any thread of the thread pool. Events can be forwarded to a channel, for example:
+/// ```ignore
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// engine.search(&SearchLimits::depth(4), Some(&|info| sender.send(info).unwrap()));
+/// ```
+pub type InfoCallback<'a> = dyn Fn(SearchInfo) + Sync + 'a;
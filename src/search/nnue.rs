@@ -0,0 +1,226 @@
+//! This module implements an optional efficiently updatable neural network (NNUE) evaluation.
+//!
+//! The network is a small dense network with a single hidden layer:
+//! * The input is a one-hot encoding of the board with 1575 features, indexed like [`crate::search::lookup::PIECE_SCORES`] (`index = piece_index*45 + cell_index`)
+//! * The hidden layer (the accumulator) is updated incrementally after each action: only the features of the cells that changed are added or removed
+//! * The output is computed from the clipped hidden layer using integer arithmetic only
+//!
+//! The weights are loaded from a file with the following little-endian layout:
+//!
+//! | Data  | Magic    | Hidden size | Feature weights      | Feature biases | Output weights | Output bias |
+//! |-------|----------|-------------|----------------------|----------------|----------------|-------------|
+//! | Type  | `b"PJNN"`| u32         | i16 × 1575 × hidden  | i16 × hidden   | i16 × hidden   | i32         |
+
+use std::fs;
+use std::path::Path;
+
+use crate::bitboard::Board;
+use crate::errors::NetworkErrorKind;
+use crate::logic::actions::{Action, ActionTrait};
+use crate::logic::index::{CellIndex, CellIndexTrait};
+use crate::logic::lookup::PIECE_TO_INDEX;
+use crate::logic::N_CELLS;
+use crate::piece::{Piece, PieceTrait};
+
+use super::Score;
+
+/// The number of input features (35 piece indices × 45 cells)
+pub const N_FEATURES: usize = 1575;
+/// The size of the hidden layer
+pub const N_HIDDEN: usize = 32;
+
+/// The magic bytes at the start of a network file
+const MAGIC: &[u8; 4] = b"PJNN";
+/// The size of a network file in bytes
+const FILE_SIZE: usize = 4 + 4 + 2 * N_FEATURES * N_HIDDEN + 2 * N_HIDDEN + 2 * N_HIDDEN + 4;
+
+/// The quantization factor of the hidden layer: hidden values are clipped to `[0, QA]`
+const QA: i16 = 255;
+/// The quantization factor of the output weights
+const QB: i64 = 64;
+/// The factor that converts the network output into a score
+const OUTPUT_SCALE: i64 = 400;
+/// The maximum absolute value of a network evaluation
+const MAX_NETWORK_SCORE: i64 = 4_096;
+
+/// Returns the feature index of a piece placed on a cell.
+#[inline(always)]
+const fn feature_index(piece: Piece, index: CellIndex) -> usize {
+    PIECE_TO_INDEX[piece as usize] * N_CELLS + index
+}
+
+/// The weights of the network.
+#[derive(Debug, Clone)]
+pub struct Network {
+    feature_weights: Vec<[i16; N_HIDDEN]>,
+    feature_biases: [i16; N_HIDDEN],
+    output_weights: [i16; N_HIDDEN],
+    output_bias: i32,
+}
+
+impl Network {
+    /// Loads a network from a weights file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, NetworkErrorKind> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Reads a network from the bytes of a weights file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NetworkErrorKind> {
+        if bytes.len() < 8 || &bytes[0..4] != MAGIC {
+            return Err(NetworkErrorKind::InvalidHeader);
+        }
+        let hidden_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        if hidden_size != N_HIDDEN {
+            return Err(NetworkErrorKind::WrongHiddenSize(hidden_size));
+        }
+        if bytes.len() != FILE_SIZE {
+            return Err(NetworkErrorKind::WrongFileSize(bytes.len()));
+        }
+
+        let mut values = bytes[8..FILE_SIZE - 4]
+            .chunks_exact(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]));
+        let mut read_layer = || {
+            let mut layer = [0; N_HIDDEN];
+            layer
+                .iter_mut()
+                .for_each(|value| *value = values.next().unwrap());
+            layer
+        };
+        let feature_weights = (0..N_FEATURES).map(|_| read_layer()).collect();
+        let feature_biases = read_layer();
+        let output_weights = read_layer();
+        let output_bias = i32::from_le_bytes(bytes[FILE_SIZE - 4..].try_into().unwrap());
+
+        Ok(Self {
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    /// Converts the network to the bytes of a weights file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FILE_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&(N_HIDDEN as u32).to_le_bytes());
+        for layer in self
+            .feature_weights
+            .iter()
+            .chain([&self.feature_biases, &self.output_weights])
+        {
+            for value in layer {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&self.output_bias.to_le_bytes());
+        bytes
+    }
+
+    /// Creates a network from its weights.
+    ///
+    /// `feature_weights` must contain one hidden layer per input feature.
+    pub fn from_weights(
+        feature_weights: Vec<[i16; N_HIDDEN]>,
+        feature_biases: [i16; N_HIDDEN],
+        output_weights: [i16; N_HIDDEN],
+        output_bias: i32,
+    ) -> Self {
+        assert_eq!(feature_weights.len(), N_FEATURES);
+        Self {
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        }
+    }
+}
+
+/// The hidden layer of the network for a given position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accumulator([i16; N_HIDDEN]);
+
+impl Accumulator {
+    /// Computes the accumulator of a position from scratch.
+    pub fn new(network: &Network, board: &Board) -> Self {
+        let mut accumulator = Self(network.feature_biases);
+        for index in board.all() {
+            accumulator.add(network, board.get_piece(index), index);
+        }
+        accumulator
+    }
+
+    #[inline(always)]
+    fn add(&mut self, network: &Network, piece: Piece, index: CellIndex) {
+        let weights = &network.feature_weights[feature_index(piece, index)];
+        for (value, weight) in self.0.iter_mut().zip(weights) {
+            *value = value.wrapping_add(*weight);
+        }
+    }
+
+    #[inline(always)]
+    fn remove(&mut self, network: &Network, piece: Piece, index: CellIndex) {
+        let weights = &network.feature_weights[feature_index(piece, index)];
+        for (value, weight) in self.0.iter_mut().zip(weights) {
+            *value = value.wrapping_sub(*weight);
+        }
+    }
+
+    /// Replaces the features of a cell that changed between two boards.
+    #[inline(always)]
+    fn update_cell(
+        &mut self,
+        network: &Network,
+        old_board: &Board,
+        new_board: &Board,
+        index: CellIndex,
+    ) {
+        let old_piece = old_board.get_piece(index);
+        let new_piece = new_board.get_piece(index);
+        if old_piece != new_piece {
+            if !old_piece.is_empty() {
+                self.remove(network, old_piece, index);
+            }
+            if !new_piece.is_empty() {
+                self.add(network, new_piece, index);
+            }
+        }
+    }
+
+    /// Updates the accumulator after an action.
+    ///
+    /// Only the cells that were modified by the action are updated (same method as [`crate::search::eval::evaluate_position_incremental`]).
+    #[inline]
+    pub fn update(
+        &mut self,
+        network: &Network,
+        old_board: &Board,
+        new_board: &Board,
+        action: Action,
+    ) {
+        let (index_start, index_mid, index_end) = action.to_indices();
+        self.update_cell(network, old_board, new_board, index_start);
+        if !index_mid.is_null() && index_mid != index_start {
+            self.update_cell(network, old_board, new_board, index_mid);
+        }
+        if index_end != index_start {
+            self.update_cell(network, old_board, new_board, index_end);
+        }
+    }
+
+    /// Returns the score of the position from the point of view of the white player.
+    #[inline]
+    pub fn evaluate(&self, network: &Network) -> Score {
+        let output: i64 = self
+            .0
+            .iter()
+            .zip(network.output_weights)
+            .map(|(&value, weight)| value.clamp(0, QA) as i64 * weight as i64)
+            .sum::<i64>()
+            + network.output_bias as i64;
+        (output * OUTPUT_SCALE / (QA as i64 * QB)).clamp(-MAX_NETWORK_SCORE, MAX_NETWORK_SCORE)
+            as Score
+    }
+}
@@ -0,0 +1,127 @@
+//! This module implements the killer move and history heuristics used to sort quiet actions (actions without captures).
+//!
+//! * Killer moves: for each ply, the last two quiet actions that caused a beta-cutoff are searched first among the quiet actions
+//! * History: each quiet action that caused a beta-cutoff gets a bonus (depth²), the remaining quiet actions are sorted by decreasing bonus
+//!
+//! The history is a butterfly table indexed by the (start, mid, end) indices of the action (see [`crate::logic::actions::ActionTrait::to_indices`]).
+//!
+//! The tables use atomic values so they can be shared between the threads of the parallel search.
+//! Concurrent updates may overwrite each other, which only affects the move ordering and not the search results.
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::Relaxed;
+
+use crate::logic::actions::{Action, ActionTrait, AtomicAction};
+use crate::logic::index::CellIndexTrait;
+use crate::logic::N_CELLS;
+
+/// The maximum ply at which the killer moves are stored
+pub const MAX_KILLER_PLY: usize = 128;
+/// The number of killer moves stored per ply
+const N_KILLERS: usize = 2;
+/// The number of possible middle indices (any cell or null)
+const N_MID_INDICES: usize = N_CELLS + 1;
+/// The size of the history table
+const HISTORY_SIZE: usize = N_CELLS * N_MID_INDICES * N_CELLS;
+/// The history values are halved when they exceed this value
+const MAX_HISTORY: u32 = 1 << 24;
+
+/// Stores the last quiet actions that caused a beta-cutoff at each ply.
+#[derive(Debug)]
+pub struct KillerTable {
+    data: Vec<[AtomicAction; N_KILLERS]>,
+}
+
+impl Default for KillerTable {
+    fn default() -> Self {
+        Self {
+            data: (0..MAX_KILLER_PLY)
+                .map(|_| [AtomicAction::new(0), AtomicAction::new(0)])
+                .collect(),
+        }
+    }
+}
+
+impl KillerTable {
+    /// Returns the killer moves stored at a given ply (null actions are stored as 0).
+    #[inline]
+    pub fn get(&self, ply: u64) -> [Action; N_KILLERS] {
+        match self.data.get(ply as usize) {
+            Some([first, second]) => [first.load(Relaxed), second.load(Relaxed)],
+            None => [0; N_KILLERS],
+        }
+    }
+
+    /// Stores a killer move at a given ply, the previous first killer move becomes the second one.
+    #[inline]
+    pub fn store(&self, ply: u64, action: Action) {
+        if let Some([first, second]) = self.data.get(ply as usize) {
+            let previous = first.swap(action, Relaxed);
+            if previous != action {
+                second.store(previous, Relaxed);
+            }
+        }
+    }
+}
+
+/// Stores the history bonus of each quiet action, indexed by (start, mid, end).
+#[derive(Debug)]
+pub struct HistoryTable {
+    data: Vec<AtomicU32>,
+}
+
+impl Default for HistoryTable {
+    fn default() -> Self {
+        Self {
+            data: (0..HISTORY_SIZE).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+}
+
+impl HistoryTable {
+    #[inline(always)]
+    fn index(action: Action) -> usize {
+        let (index_start, index_mid, index_end) = action.to_indices();
+        let index_mid = if index_mid.is_null() {
+            N_CELLS
+        } else {
+            index_mid
+        };
+        (index_start * N_MID_INDICES + index_mid) * N_CELLS + index_end
+    }
+
+    /// Returns the history bonus of an action.
+    #[inline]
+    pub fn get(&self, action: Action) -> u32 {
+        self.data[Self::index(action)].load(Relaxed)
+    }
+
+    /// Adds a bonus to an action that caused a beta-cutoff at a given depth.
+    #[inline]
+    pub fn update(&self, action: Action, depth: u64) {
+        let bonus = (depth * depth).min(MAX_HISTORY as u64) as u32;
+        let entry = &self.data[Self::index(action)];
+        let value = entry.fetch_add(bonus, Relaxed).saturating_add(bonus);
+        if value > MAX_HISTORY {
+            entry.store(value / 2, Relaxed);
+        }
+    }
+}
+
+/// The move ordering heuristics shared by all the nodes of a search.
+#[derive(Debug, Default)]
+pub struct SearchHeuristics {
+    /// The killer moves
+    pub killers: KillerTable,
+    /// The history table
+    pub history: HistoryTable,
+}
+
+impl SearchHeuristics {
+    /// Updates the heuristics after a quiet action caused a beta-cutoff.
+    #[inline]
+    pub fn update(&self, action: Action, depth: u64, ply: u64) {
+        self.killers.store(ply, action);
+        self.history.update(action, depth);
+    }
+}
@@ -0,0 +1,150 @@
+//! This module implements the skill levels used to limit the strength of the engine.
+//!
+//! Below the maximum level, the search depth is limited and the action is chosen randomly among the actions whose score is close to the best score:
+//! * The lower the level, the shallower the search and the wider the score margin
+//! * Better actions are more likely to be chosen
+//! * Immediate wins are always played, actions that lose by force are never chosen if another action does not lose, and a forced win is never given away
+//! * With a time limit, part of the time is reserved for choosing the action (see [`SKILL_TIME_DIVISOR`])
+
+use std::cmp::{max, min};
+
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::bitboard::Board;
+use crate::hash::position::HashTrait;
+use crate::logic::actions::Action;
+use crate::logic::Player;
+
+use super::alphabeta::{search_node, SearchContext, SearchLine, BASE_ALPHA, BASE_BETA};
+use super::eval::{win_score, StaticEval, MATE_THRESHOLD};
+use super::{NodeType, Score};
+
+/// The maximum skill level (full strength)
+pub const MAX_SKILL_LEVEL: u64 = 20;
+/// The number of skill levels per additional ply of search depth
+const LEVELS_PER_PLY: u64 = 4;
+/// The score margin added per skill level below the maximum level
+const MARGIN_PER_LEVEL: Score = 10;
+/// Below the maximum level, the main search uses the time budget divided by this value, the rest is left to the skill search
+pub const SKILL_TIME_DIVISOR: u32 = 2;
+
+/// Returns the maximum search depth at a given skill level (no limit at the maximum level).
+pub fn skill_depth(level: u64) -> u64 {
+    if level >= MAX_SKILL_LEVEL {
+        u64::MAX
+    } else {
+        1 + level / LEVELS_PER_PLY
+    }
+}
+
+/// Returns the maximum score difference between the best action and the chosen action at a given skill level.
+pub fn skill_margin(level: u64) -> Score {
+    (MAX_SKILL_LEVEL - level.min(MAX_SKILL_LEVEL)) as Score * MARGIN_PER_LEVEL
+}
+
+/// Returns the score of every available action at a given depth (in the order of [`Board::available_player_actions`]).
+///
+/// Unlike [`super::alphabeta::search_root`], every action is searched with the same window so the scores can be compared with each other.
+/// The scores inside the window are exact, the scores outside are bounds.
+pub fn score_root_actions(
+    board: &Board,
+    current_player: Player,
+    depth: u64,
+    (alpha, beta): (Score, Score),
+    context: &SearchContext,
+) -> Vec<(Action, Score)> {
+    let static_eval = StaticEval::new(board, context);
+    let root_line = SearchLine::root(context.half_moves, context.history);
+    let root_hash = (board, current_player).hash();
+    let score_action = |&action: &Action| {
+        let score = if board.is_action_win(action, current_player) {
+            win_score(1)
+        } else {
+            let mut new_board = *board;
+            new_board.play_action(action);
+            -search_node(
+                (&new_board, 1 - current_player),
+                depth.max(1) - 1,
+                root_line.next(root_hash, new_board.count_pieces() != board.count_pieces()),
+                (-beta, -alpha),
+                NodeType::PV,
+                static_eval.update(board, &new_board, action, context),
+                context,
+            )
+        };
+        (action, score)
+    };
+    let available_actions = board.available_player_actions(current_player);
+    let available_actions = &available_actions[0..available_actions.len()];
+    if context.parallel {
+        available_actions.par_iter().map(score_action).collect()
+    } else {
+        available_actions.iter().map(score_action).collect()
+    }
+}
+
+/// Chooses an action among scored actions at a given skill level (see [`score_root_actions`]).
+///
+/// The candidates are the actions whose score is within the margin of the best score, they are chosen with a probability that decreases with their distance to the best score.
+pub fn choose_skill_action<R: Rng>(
+    scored_actions: &[(Action, Score)],
+    level: u64,
+    rng: &mut R,
+) -> Option<(Action, Score)> {
+    let &(best_action, best_score) = scored_actions
+        .iter()
+        .rev()
+        .max_by_key(|(_action, score)| *score)?;
+    if level >= MAX_SKILL_LEVEL || best_score >= MATE_THRESHOLD {
+        return Some((best_action, best_score));
+    }
+    let margin = skill_margin(level);
+    let candidates: Vec<(Action, Score, u32)> = scored_actions
+        .iter()
+        .filter(|&&(_action, score)| {
+            best_score - score <= margin
+                && (score > -MATE_THRESHOLD || best_score <= -MATE_THRESHOLD)
+        })
+        .map(|&(action, score)| (action, score, (margin - (best_score - score)) as u32 + 1))
+        .collect();
+    let total_weight: u32 = candidates
+        .iter()
+        .map(|&(_action, _score, weight)| weight)
+        .sum();
+    let mut value = rng.random_range(0..total_weight);
+    for (action, score, weight) in candidates {
+        if value < weight {
+            return Some((action, score));
+        }
+        value -= weight;
+    }
+    Some((best_action, best_score))
+}
+
+/// Searches and chooses an action at a given skill level, knowing the score of the best action at this depth.
+///
+/// The actions are searched with a window around the best score that only keeps the exact scores of the candidates.
+/// Returns None if no action is inside the window (the best score was not accurate) or if the search was stopped (the scores are not reliable).
+pub fn search_skill<R: Rng>(
+    board: &Board,
+    current_player: Player,
+    (depth, best_score): (u64, Score),
+    level: u64,
+    context: &SearchContext,
+    rng: &mut R,
+) -> Option<(Action, Score)> {
+    let margin = skill_margin(level);
+    let alpha = max(best_score.saturating_sub(margin + 1), BASE_ALPHA);
+    let beta = min(best_score.saturating_add(margin + 1), BASE_BETA);
+    let scored_actions = score_root_actions(board, current_player, depth, (alpha, beta), context);
+    if context.is_stopped() {
+        return None;
+    }
+    let scored_actions: Vec<(Action, Score)> = scored_actions
+        .into_iter()
+        // Fail-low scores are upper bounds, the actions may be much worse
+        .filter(|&(_action, score)| score > alpha)
+        .collect();
+    choose_skill_action(&scored_actions, level, rng)
+}
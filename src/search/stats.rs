@@ -0,0 +1,120 @@
+//! This module implements the statistics collected during a search.
+//!
+//! The statistics are only collected if a [`SearchStats`] is given to the search context, so the search has no overhead otherwise.
+//! The counters are atomic values so they can be shared between the threads of the parallel search.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Mutex;
+
+/// The counters of a search.
+#[derive(Debug, Default)]
+pub struct SearchStats {
+    /// The number of nodes searched by the alphabeta search
+    pub nodes: AtomicU64,
+    /// The number of nodes searched by the quiescence search
+    pub qnodes: AtomicU64,
+    /// The number of transposition table reads
+    pub tt_probes: AtomicU64,
+    /// The number of transposition table reads that found an entry
+    pub tt_hits: AtomicU64,
+    /// The number of nodes that returned the transposition table score
+    pub tt_cutoffs: AtomicU64,
+    /// The number of beta-cutoffs
+    pub beta_cutoffs: AtomicU64,
+    /// The number of beta-cutoffs caused by the first action searched
+    pub first_move_cutoffs: AtomicU64,
+    /// The number of actions searched again with the full window after failing high with the null window
+    pub pvs_researches: AtomicU64,
+    /// The total number of nodes (alphabeta and quiescence) at the end of each completed iteration, by depth
+    depth_nodes: Mutex<Vec<(u64, u64)>>,
+}
+
+impl SearchStats {
+    /// Increments a counter.
+    #[inline]
+    pub fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Relaxed);
+    }
+
+    /// Returns the total number of nodes (alphabeta and quiescence).
+    pub fn total_nodes(&self) -> u64 {
+        self.nodes.load(Relaxed) + self.qnodes.load(Relaxed)
+    }
+
+    /// Records the total number of nodes at the end of an iteration of the iterative deepening.
+    pub fn end_iteration(&self, depth: u64) {
+        self.depth_nodes
+            .lock()
+            .unwrap()
+            .push((depth, self.total_nodes()));
+    }
+
+    /// Returns the number of nodes searched by each completed iteration and the effective branching factor (ratio with the previous iteration), by depth.
+    pub fn iterations(&self) -> Vec<(u64, u64, Option<f64>)> {
+        let depth_nodes = self.depth_nodes.lock().unwrap();
+        let mut previous_total = 0;
+        let mut previous_nodes: Option<u64> = None;
+        depth_nodes
+            .iter()
+            .map(|&(depth, total)| {
+                let nodes = total - previous_total;
+                let ebf = previous_nodes
+                    .filter(|&previous_nodes| previous_nodes > 0)
+                    .map(|previous_nodes| nodes as f64 / previous_nodes as f64);
+                previous_total = total;
+                previous_nodes = Some(nodes);
+                (depth, nodes, ebf)
+            })
+            .collect()
+    }
+
+    /// Returns a snapshot of the statistics.
+    pub fn summary(&self) -> StatsSummary {
+        StatsSummary {
+            nodes: self.nodes.load(Relaxed),
+            qnodes: self.qnodes.load(Relaxed),
+            tt_probes: self.tt_probes.load(Relaxed),
+            tt_hits: self.tt_hits.load(Relaxed),
+            tt_cutoffs: self.tt_cutoffs.load(Relaxed),
+            beta_cutoffs: self.beta_cutoffs.load(Relaxed),
+            first_move_cutoffs: self.first_move_cutoffs.load(Relaxed),
+            pvs_researches: self.pvs_researches.load(Relaxed),
+            iterations: self.iterations(),
+        }
+    }
+}
+
+/// A snapshot of the statistics of a search (see [`SearchStats`]).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsSummary {
+    /// The number of nodes searched by the alphabeta search
+    pub nodes: u64,
+    /// The number of nodes searched by the quiescence search
+    pub qnodes: u64,
+    /// The number of transposition table reads
+    pub tt_probes: u64,
+    /// The number of transposition table reads that found an entry
+    pub tt_hits: u64,
+    /// The number of nodes that returned the transposition table score
+    pub tt_cutoffs: u64,
+    /// The number of beta-cutoffs
+    pub beta_cutoffs: u64,
+    /// The number of beta-cutoffs caused by the first action searched
+    pub first_move_cutoffs: u64,
+    /// The number of actions searched again with the full window after failing high with the null window
+    pub pvs_researches: u64,
+    /// The depth, number of nodes and effective branching factor of each completed iteration
+    pub iterations: Vec<(u64, u64, Option<f64>)>,
+}
+
+impl StatsSummary {
+    /// Returns the proportion of beta-cutoffs caused by the first action searched (between 0 and 1).
+    pub fn first_move_cutoff_rate(&self) -> f64 {
+        if self.beta_cutoffs == 0 {
+            0.
+        } else {
+            self.first_move_cutoffs as f64 / self.beta_cutoffs as f64
+        }
+    }
+}
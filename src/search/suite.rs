@@ -0,0 +1,192 @@
+//! This module implements a runner for test suites of positions (similar to chess EPD suites).
+//!
+//! A suite is a text file with one position per line. Empty lines and lines starting with `#` are ignored.
+//!
+//! Each line contains a position in Pijersi Standard Notation followed by `;`-separated operations:
+//! * `bm [moves]`: the best moves, the search must choose one of them
+//! * `am [moves]`: the moves to avoid, the search must not choose any of them
+//! * `score [min] [max]`: the range of the expected score (from the point of view of the side to move, bounds included)
+//! * `id [name]`: the name of the position
+//!
+//! For example:
+//! ```not_rust
+//! s-p-r-s-p-r-/p-r-s-wwr-s-p-/6/7/6/P-S-R-WWS-R-P-/R-P-S-R-P-S- w 0 1 ; am a1b1 ; score -100 100 ; id startpos
+//! ```
+
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::bitboard::Board;
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::game::{Game, GameOptions};
+use crate::hash::search::SearchTable;
+use crate::logic::actions::Action;
+use crate::logic::translate::string_to_action;
+
+use super::Score;
+
+/// A position of a test suite and its expected results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteEntry {
+    /// The position in Pijersi Standard Notation
+    pub psn: String,
+    /// The board of the position
+    pub board: Board,
+    /// The name of the position
+    pub id: Option<String>,
+    /// The best actions (the search must choose one of them if not empty)
+    pub best_actions: Vec<Action>,
+    /// The actions to avoid (the search must not choose any of them)
+    pub avoid_actions: Vec<Action>,
+    /// The range of the expected score (bounds included)
+    pub score_range: Option<(Score, Score)>,
+}
+
+impl SuiteEntry {
+    /// Parses a line of a test suite.
+    ///
+    /// The position is validated like the positions set from Pijersi Standard Notation with the given options (see [`Game::set_string_state`]).
+    pub fn parse(line: &str, options: &GameOptions) -> Result<Self, ParseError> {
+        let invalid_line = || ParseError {
+            kind: ParseErrorKind::InvalidSuiteLine,
+            value: line.to_owned(),
+        };
+
+        let mut operations = line.split(';').map(str::trim);
+        let psn = operations.next().ok_or_else(invalid_line)?;
+        let mut game = Game::new();
+        game.options.allow_unusual_positions = options.allow_unusual_positions;
+        game.set_string_state(psn)?;
+
+        let mut entry = Self {
+            psn: psn.to_owned(),
+            board: game.board,
+            id: None,
+            best_actions: vec![],
+            avoid_actions: vec![],
+            score_range: None,
+        };
+        for operation in operations.filter(|operation| !operation.is_empty()) {
+            let words: Vec<&str> = operation.split_whitespace().collect();
+            match words[..] {
+                ["bm", ref actions @ ..] if !actions.is_empty() => {
+                    for action_string in actions {
+                        entry
+                            .best_actions
+                            .push(string_to_action(&game.board, action_string)?);
+                    }
+                }
+                ["am", ref actions @ ..] if !actions.is_empty() => {
+                    for action_string in actions {
+                        entry
+                            .avoid_actions
+                            .push(string_to_action(&game.board, action_string)?);
+                    }
+                }
+                ["score", min, max] => {
+                    let parse_score = |value: &str| {
+                        value.parse::<Score>().map_err(|err| ParseError {
+                            kind: ParseErrorKind::InvalidInt(err),
+                            value: value.to_owned(),
+                        })
+                    };
+                    entry.score_range = Some((parse_score(min)?, parse_score(max)?));
+                }
+                ["id", ref name @ ..] if !name.is_empty() => {
+                    entry.id = Some(name.join(" "));
+                }
+                _ => return Err(invalid_line()),
+            }
+        }
+        Ok(entry)
+    }
+
+    /// Returns whether a search result satisfies the expected results of this position.
+    pub fn check(&self, action: Action, score: Score) -> bool {
+        (self.best_actions.is_empty() || self.best_actions.contains(&action))
+            && !self.avoid_actions.contains(&action)
+            && match self.score_range {
+                Some((min, max)) => (min..=max).contains(&score),
+                None => true,
+            }
+    }
+}
+
+/// Parses a test suite (one position per line, empty lines and lines starting with `#` are ignored).
+///
+/// The positions are validated with the given options (see [`SuiteEntry::parse`]).
+pub fn parse_suite(
+    suite_string: &str,
+    options: &GameOptions,
+) -> Result<Vec<SuiteEntry>, ParseError> {
+    suite_string
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| SuiteEntry::parse(line, options))
+        .collect()
+}
+
+/// The search limit used for each position of a test suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuiteLimit {
+    /// Searches up to a given depth
+    Depth(u64),
+    /// Searches for a given time in milliseconds
+    Movetime(u64),
+}
+
+/// The result of the search on a position of a test suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuiteResult {
+    /// The action found by the search (None if there is no legal action)
+    pub action: Option<Action>,
+    /// The score found by the search
+    pub score: Score,
+    /// Whether the search result satisfies the expected results
+    pub passed: bool,
+    /// The search duration
+    pub duration: Duration,
+}
+
+/// Runs the search on every position of a test suite and returns the results.
+///
+/// The positions are searched in a separate game with the options of the given game, which is left unchanged.
+/// The transposition table (if used) is not emptied between the positions.
+pub fn run_suite(
+    game: &Game,
+    entries: &[SuiteEntry],
+    limit: SuiteLimit,
+    transposition_table: Option<&RwLock<SearchTable>>,
+) -> Vec<SuiteResult> {
+    let options = game.options.clone();
+    let mut game = Game::new();
+    game.options = options;
+    entries
+        .iter()
+        .map(|entry| {
+            // The positions have been validated when parsing the suite
+            game.set_string_state(&entry.psn).unwrap();
+            let start_time = Instant::now();
+            let result = match limit {
+                SuiteLimit::Depth(depth) => game.search_to_depth(depth, None, transposition_table),
+                SuiteLimit::Movetime(time) => game.search_to_time(time, None, transposition_table),
+            };
+            let duration = start_time.elapsed();
+            match result {
+                Some((action, score)) => SuiteResult {
+                    action: Some(action),
+                    score,
+                    passed: entry.check(action, score),
+                    duration,
+                },
+                None => SuiteResult {
+                    action: None,
+                    score: 0,
+                    passed: false,
+                    duration,
+                },
+            }
+        })
+        .collect()
+}
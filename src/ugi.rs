@@ -4,27 +4,36 @@
 
 use clap::{Args, Parser, Subcommand};
 use current_platform::{COMPILED_ON, CURRENT_PLATFORM};
-use std::{process::exit, sync::RwLock, time::Instant};
+use std::{fs, process::exit, time::Instant};
 
 use crate::{
     bitboard::Board,
-    errors::{get_error_trace, ParseError, RuntimeError, UgiErrorKind},
-    game::Game,
-    hash::search::SearchTable,
+    datagen::{write_binary, write_text, DatagenOptions},
+    engine::{Engine, DEFAULT_THREADS},
+    errors::{get_error_trace, ParseError, ParseErrorKind, RuntimeError, UgiErrorKind},
+    game::{Game, SearchLimits},
     logic::{
+        narration::{describe_cells, describe_pieces, narrate_action},
         perft::perft,
         rules::is_action_legal,
         translate::{action_to_string, string_to_action, string_to_player},
     },
     search::{
-        alphabeta::{BASE_ALPHA, BASE_BETA},
-        eval::{evaluate_position, evaluate_position_for_player, quiescence_search},
-        openings::OpeningBook,
+        alphabeta::{score_to_string, SearchMode, BASE_ALPHA, BASE_BETA},
+        eval::{quiescence_search, EvalTerm, EvalTrace, StaticEval},
+        info::{ScoreBound, SearchInfo},
+        skill::MAX_SKILL_LEVEL,
+        stats::StatsSummary,
+        suite::{parse_suite, SuiteLimit},
+        Score, SearchResult,
     },
-    utils::parse_bool_arg,
+    utils::{parse_bool_arg, parse_eval_term, parse_search_mode},
     AUTHOR_NAME, ENGINE_NAME, VERSION,
 };
 
+#[cfg(feature = "nnue")]
+use crate::search::nnue::Network;
+
 #[derive(Parser, Debug)]
 #[command(no_binary_name(true))]
 struct UgiParser {
@@ -38,6 +47,7 @@ enum Commands {
     Isready,
     Uginewgame,
     Quit,
+    Datagen(DatagenArgs),
     #[command(subcommand)]
     Go(GoArgs),
     #[command(subcommand)]
@@ -46,6 +56,8 @@ enum Commands {
     Query(QueryArgs),
     #[command(subcommand)]
     Setoption(SetoptionArgs),
+    #[command(subcommand)]
+    Suite(SuiteArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -56,6 +68,22 @@ enum GoArgs {
     Perft { depth: u64 },
 }
 
+#[derive(Subcommand, Debug)]
+enum SuiteArgs {
+    Depth { depth: u64, path: String },
+    Movetime { time: u64, path: String },
+}
+
+#[derive(Args, Debug)]
+struct DatagenArgs {
+    games: u64,
+    depth: u64,
+    random_plies: u64,
+    seed: u64,
+    output: String,
+    nodes: Option<u64>,
+}
+
 #[derive(Subcommand, Debug)]
 enum PositionArgs {
     Startpos(StartposArgs),
@@ -83,22 +111,84 @@ enum QueryArgs {
     Result,
     Islegal { action_string: String },
     Fen,
+    Describe,
+    Describepieces,
     Eval,
+    Evaltrace,
     QS,
 }
 
 #[derive(Subcommand, Debug)]
 enum SetoptionArgs {
-    UseBook { value: String },
-    UseTable { value: String },
-    Verbose { value: String },
+    UseBook {
+        value: String,
+    },
+    UseTable {
+        value: String,
+    },
+    Verbose {
+        value: String,
+    },
+    Narrate {
+        value: String,
+    },
+    Debug {
+        value: String,
+    },
+    UseLmr {
+        value: String,
+    },
+    UseNullMove {
+        value: String,
+    },
+    UseThreatExtensions {
+        value: String,
+    },
+    Threads {
+        #[arg(value_parser = clap::value_parser!(u64).range(1..=MAX_THREADS as u64))]
+        value: u64,
+    },
+    SearchMode {
+        value: String,
+    },
+    Deterministic {
+        value: String,
+    },
+    AllowUnusualPositions {
+        value: String,
+    },
+    Contempt {
+        #[arg(allow_negative_numbers = true, value_parser = clap::value_parser!(Score).range(-MAX_CONTEMPT as i64..=MAX_CONTEMPT as i64))]
+        value: Score,
+    },
+    SkillLevel {
+        #[arg(value_parser = clap::value_parser!(u64).range(0..=MAX_SKILL_LEVEL))]
+        value: u64,
+    },
+    EvalWeight {
+        term: String,
+        #[arg(allow_negative_numbers = true)]
+        value: Score,
+    },
+    #[cfg(feature = "nnue")]
+    EvalFile {
+        path: String,
+    },
 }
 
+/// The maximum number of threads of the thread pool
+pub const MAX_THREADS: usize = 1024;
+/// The maximum absolute value of the contempt option
+pub const MAX_CONTEMPT: Score = 1000;
+
 /// The `UgiEngine` struct that implements the UGI protocol.
+///
+/// It is a thin layer over [`Engine`] that parses the commands and formats the search events as `info` lines.
+/// The commands are run in the engine's thread pool, its size is set by the `threads` option.
 pub struct UgiEngine {
-    game: Game,
-    opening_book: Option<OpeningBook>,
-    transposition_table: Option<RwLock<SearchTable>>,
+    engine: Engine,
+    verbose: bool,
+    narrate: bool,
 }
 
 impl Default for UgiEngine {
@@ -110,13 +200,11 @@ impl Default for UgiEngine {
 impl UgiEngine {
     /// Creates a new `UgiEngine`
     pub fn new() -> Self {
-        let mut new_self = Self {
-            game: Game::default(),
-            opening_book: None,
-            transposition_table: None,
-        };
-        new_self.game.init();
-        new_self
+        Self {
+            engine: Engine::new(),
+            verbose: true,
+            narrate: false,
+        }
     }
 
     fn ugi(&self) {
@@ -124,18 +212,41 @@ impl UgiEngine {
         println!("id author {AUTHOR_NAME}");
         println!("info target platform {CURRENT_PLATFORM} compiled on {COMPILED_ON}");
         println!("option name verbose type check default true");
+        println!("option name narrate type check default false");
+        println!("option name debug type check default false");
         println!("option name use-book type check default true");
+        println!("option name use-lmr type check default true");
+        println!("option name use-null-move type check default true");
+        println!("option name use-threat-extensions type check default true");
+        println!("option name threads type spin default {DEFAULT_THREADS} min 1 max {MAX_THREADS}");
+        println!(
+            "option name search-mode type combo default {} var {}",
+            SearchMode::default().name(),
+            SearchMode::ALL.map(SearchMode::name).join(" var ")
+        );
+        println!("option name deterministic type check default false");
+        println!("option name allow-unusual-positions type check default false");
+        println!("option name contempt type spin default 0 min -{MAX_CONTEMPT} max {MAX_CONTEMPT}");
+        println!("option name skill-level type spin default {MAX_SKILL_LEVEL} min 0 max {MAX_SKILL_LEVEL}");
+        #[cfg(feature = "nnue")]
+        println!("option name eval-file type string default none");
+        for term in EvalTerm::ALL {
+            println!(
+                "option name eval-weight {} type spin default {}",
+                term.name(),
+                self.engine.game.options.eval_weights.get(term)
+            );
+        }
         println!("ugiok");
     }
 
     fn isready(&mut self) {
-        self.opening_book = Some(OpeningBook::new());
-        self.transposition_table = Some(RwLock::new(SearchTable::default()));
+        self.engine.load();
         println!("readyok");
     }
 
     fn uginewgame(&mut self) {
-        self.game.init();
+        self.engine.new_game();
     }
 
     // TODO: help function?
@@ -145,44 +256,29 @@ impl UgiEngine {
 
     fn go(&mut self, go_args: GoArgs) {
         match go_args {
-            GoArgs::Depth { depth } => {
-                let result = self.game.search_to_depth(
-                    depth,
-                    self.opening_book.as_ref(),
-                    self.transposition_table.as_ref(),
-                );
-                let action_string = if let Some((action, _score)) = result {
-                    action_to_string(&self.game.board, action)
-                } else {
-                    println!("info null move");
-                    "------".to_owned()
-                };
-                println!("bestmove {action_string}");
-            }
-            GoArgs::Movetime { time } => {
-                let action = self.game.search_to_time(
-                    time,
-                    self.opening_book.as_ref(),
-                    self.transposition_table.as_ref(),
-                );
-                let action_string = if let Some((action, _score)) = action {
-                    action_to_string(&self.game.board, action)
-                } else {
-                    println!("info null move");
-                    "------".to_owned()
-                };
-                println!("bestmove {action_string}");
-            }
+            GoArgs::Depth { depth } => self.search(&SearchLimits::depth(depth)),
+            GoArgs::Movetime { time } => self.search(&SearchLimits::movetime(time)),
             GoArgs::Manual { action_string } => {
-                let result = self.game.play_from_string(&action_string);
+                let board = self.engine.game.board;
+                let result = self.engine.game.play_from_string(&action_string);
                 match result {
-                    Ok(_v) => (),
+                    Ok(_v) => {
+                        if self.narrate {
+                            // The action string is valid since it was played
+                            let action = string_to_action(&board, &action_string).unwrap();
+                            println!("info narration {}", narrate_action(&board, action));
+                        }
+                    }
                     Err(e) => print_error_trace(&e),
                 }
             }
             GoArgs::Perft { depth } => {
                 let start_time = Instant::now();
-                let count = perft(&self.game.board, self.game.current_player, depth);
+                let count = perft(
+                    &self.engine.game.board,
+                    self.engine.game.current_player,
+                    depth,
+                );
                 let duration = start_time.elapsed();
                 let nps = count as u128 * 1_000_000_000 / duration.as_nanos();
                 let duration_ms = duration.as_millis();
@@ -191,13 +287,61 @@ impl UgiEngine {
         }
     }
 
+    fn search(&self, limits: &SearchLimits) {
+        let board = &self.engine.game.board;
+        let print_info = |info: SearchInfo| {
+            // The statistics are printed in debug mode even if the engine is not verbose
+            if self.verbose || matches!(info, SearchInfo::Stats(_)) {
+                for line in info_to_lines(board, &info) {
+                    println!("{line}");
+                }
+            }
+        };
+        let result = self.engine.search(limits, Some(&print_info));
+        let action_string = if let Some(SearchResult { action, .. }) = result {
+            let action = action.action();
+            if self.narrate {
+                println!("info narration {}", narrate_action(board, action));
+            }
+            action_to_string(board, action)
+        } else {
+            println!("info null move");
+            "------".to_owned()
+        };
+        println!("bestmove {action_string}");
+    }
+
+    fn datagen(&self, datagen_args: DatagenArgs) {
+        let options = DatagenOptions {
+            games: datagen_args.games,
+            depth: datagen_args.depth,
+            nodes: datagen_args.nodes,
+            random_plies: datagen_args.random_plies,
+            seed: datagen_args.seed,
+        };
+        let start_time = Instant::now();
+        let positions = self.engine.generate_data(&options);
+        let duration_ms = start_time.elapsed().as_millis();
+        let output = datagen_args.output;
+        let result = write_binary(format!("{output}.bin"), &positions)
+            .and_then(|_| write_text(format!("{output}.txt"), &positions));
+        match result {
+            Ok(_v) => println!(
+                "info datagen games {} positions {} time {duration_ms}",
+                options.games,
+                positions.len()
+            ),
+            Err(e) => print_error_trace(&RuntimeError::Data(e)),
+        }
+    }
+
     fn position(&mut self, position_args: PositionArgs) {
         match position_args {
             PositionArgs::Startpos(startpos_args) => {
                 let action_list = startpos_args.moves;
                 match action_list.len() {
                     0 => {
-                        self.game.init();
+                        self.engine.game.init();
                     }
                     1 => print_error_trace(&RuntimeError::UGI(UgiErrorKind::InvalidUGIPosition(
                         action_list.join(" "),
@@ -206,8 +350,8 @@ impl UgiEngine {
                         UgiErrorKind::InvalidUGIPosition(action_list.join(" ")),
                     )),
                     _ => {
-                        self.game.init();
-                        play_actions(&mut self.game, &action_list[1..]);
+                        self.engine.game.init();
+                        play_actions(&mut self.engine.game, &action_list[1..]);
                     }
                 }
             }
@@ -215,7 +359,7 @@ impl UgiEngine {
                 let action_list: &Vec<String> = &fen_args.moves;
                 match action_list.len() {
                     0 => {
-                        set_fen(&mut self.game, &fen_args);
+                        set_fen(&mut self.engine.game, &fen_args);
                     }
                     1 => print_error_trace(&RuntimeError::UGI(UgiErrorKind::InvalidUGIPosition(
                         action_list.join(" "),
@@ -224,8 +368,8 @@ impl UgiEngine {
                         UgiErrorKind::InvalidUGIPosition(action_list.join(" ")),
                     )),
                     _ => {
-                        set_fen(&mut self.game, &fen_args);
-                        play_actions(&mut self.game, &action_list[1..]);
+                        set_fen(&mut self.engine.game, &fen_args);
+                        play_actions(&mut self.engine.game, &action_list[1..]);
                     }
                 }
             }
@@ -235,22 +379,22 @@ impl UgiEngine {
     fn query(&self, query_args: QueryArgs) {
         match query_args {
             QueryArgs::Gameover => {
-                if self.game.is_win() || self.game.is_draw() {
+                if self.engine.game.is_win() || self.engine.game.is_draw() {
                     println!("response true");
                 } else {
                     println!("response false");
                 }
             }
             QueryArgs::P1turn => {
-                if self.game.current_player == 0 {
+                if self.engine.game.current_player == 0 {
                     println!("response true");
                 } else {
                     println!("response false");
                 }
             }
             QueryArgs::Result => {
-                if self.game.is_win() {
-                    let winner = self.game.get_winner();
+                if self.engine.game.is_win() {
+                    let winner = self.engine.game.get_winner();
                     match winner {
                         Some(0) => {
                             println!("response p1win");
@@ -262,17 +406,21 @@ impl UgiEngine {
                             println!("response none");
                         }
                     };
-                } else if self.game.is_draw() {
+                } else if self.engine.game.is_draw() {
                     println!("response draw");
                 } else {
                     println!("response none");
                 }
             }
             QueryArgs::Islegal { action_string } => {
-                let action_result = string_to_action(&self.game.board, &action_string);
+                let action_result = string_to_action(&self.engine.game.board, &action_string);
                 match action_result {
                     Ok(action) => {
-                        if is_action_legal(&self.game.board, self.game.current_player, action) {
+                        if is_action_legal(
+                            &self.engine.game.board,
+                            self.engine.game.current_player,
+                            action,
+                        ) {
                             println!("response true");
                         } else {
                             println!("response false");
@@ -284,23 +432,52 @@ impl UgiEngine {
                 }
             }
             QueryArgs::Fen => {
-                println!("{}", self.game.get_string_state());
+                println!("{}", self.engine.game.get_string_state());
+            }
+            QueryArgs::Describe => {
+                println!("{}", describe_cells(&self.engine.game.board));
+            }
+            QueryArgs::Describepieces => {
+                println!("{}", describe_pieces(&self.engine.game.board));
             }
             QueryArgs::Eval => {
+                let context = self.engine.game.search_context(None, None);
+                let eval = StaticEval::new(&self.engine.game.board, &context)
+                    .evaluate(&self.engine.game.board, &context);
                 println!(
                     "info eval {}",
-                    evaluate_position_for_player(&self.game.board, self.game.current_player)
+                    if self.engine.game.current_player == 0 {
+                        eval
+                    } else {
+                        -eval
+                    }
+                );
+            }
+            QueryArgs::Evaltrace => {
+                // The trace only describes the piece-square tables and the terms
+                #[cfg(feature = "nnue")]
+                if self.engine.game.options.network.is_some() {
+                    print_error_trace(&RuntimeError::UGI(UgiErrorKind::EvalTraceWithNetwork));
+                    return;
+                }
+                let trace = EvalTrace::new(
+                    &self.engine.game.board,
+                    &self.engine.game.options.eval_weights,
                 );
+                println!("{}", trace.to_pretty_string());
             }
             QueryArgs::QS => {
-                let static_eval = evaluate_position(&self.game.board);
+                let context = self.engine.game.search_context(None, None);
+                let static_eval = StaticEval::new(&self.engine.game.board, &context);
                 println!(
                     "info qs {}",
                     quiescence_search(
-                        &self.game.board,
-                        self.game.current_player,
+                        &self.engine.game.board,
+                        self.engine.game.current_player,
+                        0,
                         (BASE_ALPHA, BASE_BETA),
                         static_eval,
+                        &context,
                     )
                 );
             }
@@ -311,29 +488,149 @@ impl UgiEngine {
         match option {
             SetoptionArgs::UseBook { value } => match parse_bool_arg(&value) {
                 Ok(value) => {
-                    self.game.options.use_book = value;
+                    self.engine.game.options.use_book = value;
                 }
                 Err(e) => print_error_trace(&e),
             },
             SetoptionArgs::UseTable { value } => match parse_bool_arg(&value) {
                 Ok(value) => {
-                    self.game.options.use_table = value;
+                    self.engine.game.options.use_table = value;
                 }
                 Err(e) => print_error_trace(&e),
             },
             SetoptionArgs::Verbose { value } => match parse_bool_arg(&value) {
                 Ok(value) => {
-                    self.game.options.verbose = value;
+                    self.verbose = value;
                 }
                 Err(e) => print_error_trace(&e),
             },
+            SetoptionArgs::Narrate { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.narrate = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::Debug { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.debug = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::UseLmr { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.use_lmr = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::UseNullMove { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.use_null_move = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::UseThreatExtensions { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.use_threat_extensions = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::Threads { value } => {
+                if let Err(e) = self.engine.set_threads(value as usize) {
+                    print_error_trace(&RuntimeError::UGI(UgiErrorKind::ThreadPool(e)));
+                }
+            }
+            SetoptionArgs::SearchMode { value } => match parse_search_mode(&value) {
+                Ok(value) => {
+                    self.engine.game.options.search_mode = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::Deterministic { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.deterministic = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::AllowUnusualPositions { value } => match parse_bool_arg(&value) {
+                Ok(value) => {
+                    self.engine.game.options.allow_unusual_positions = value;
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            SetoptionArgs::Contempt { value } => {
+                self.engine.game.options.contempt = value;
+            }
+            SetoptionArgs::SkillLevel { value } => {
+                self.engine.game.options.skill_level = value;
+            }
+            SetoptionArgs::EvalWeight { term, value } => match parse_eval_term(&term) {
+                Ok(term) => {
+                    self.engine.set_eval_weight(term, value);
+                }
+                Err(e) => print_error_trace(&e),
+            },
+            #[cfg(feature = "nnue")]
+            SetoptionArgs::EvalFile { path } => {
+                if path == "none" {
+                    self.engine.set_network(None);
+                } else {
+                    match Network::load(&path) {
+                        Ok(network) => {
+                            self.engine.set_network(Some(network));
+                        }
+                        Err(e) => print_error_trace(&RuntimeError::Network(e)),
+                    }
+                }
+            }
         }
     }
 
+    fn suite(&mut self, suite_args: SuiteArgs) {
+        let (limit, path) = match suite_args {
+            SuiteArgs::Depth { depth, path } => (SuiteLimit::Depth(depth), path),
+            SuiteArgs::Movetime { time, path } => (SuiteLimit::Movetime(time), path),
+        };
+        let entries = match fs::read_to_string(&path) {
+            Ok(suite_string) => match parse_suite(&suite_string, &self.engine.game.options) {
+                Ok(entries) => entries,
+                Err(e) => return print_error_trace(&RuntimeError::Parse(e)),
+            },
+            Err(e) => return print_error_trace(&RuntimeError::UGI(UgiErrorKind::ReadFile(e))),
+        };
+        let results = self.engine.run_suite(&entries, limit);
+        for (index, (entry, result)) in entries.iter().zip(&results).enumerate() {
+            let id = entry.id.clone().unwrap_or_else(|| (index + 1).to_string());
+            let action_string = match result.action {
+                Some(action) => action_to_string(&entry.board, action),
+                None => "------".to_owned(),
+            };
+            println!(
+                "info suite id {id} result {} bestmove {action_string} score {} time {}",
+                if result.passed { "pass" } else { "fail" },
+                score_to_string(result.score),
+                result.duration.as_millis()
+            );
+        }
+        let passed = results.iter().filter(|result| result.passed).count();
+        let duration_ms: u128 = results
+            .iter()
+            .map(|result| result.duration.as_millis())
+            .sum();
+        println!(
+            "info suite passed {passed} total {} time {duration_ms}",
+            results.len()
+        );
+    }
+
     /// Reads a command and responds to it (using stdout).
     ///
-    /// The parsing is done using the clap crate.
+    /// The parsing is done using the clap crate. The command is run in the engine's thread pool.
     pub fn get_command(&mut self, command: &str) {
+        let thread_pool = self.engine.thread_pool();
+        thread_pool.install(|| self.run_command(command));
+    }
+
+    fn run_command(&mut self, command: &str) {
         let words: Vec<&str> = command.split_whitespace().collect();
         let parse_results = UgiParser::try_parse_from(words);
 
@@ -343,10 +640,12 @@ impl UgiEngine {
                 Commands::Isready => self.isready(),
                 Commands::Uginewgame => self.uginewgame(),
                 Commands::Quit => self.quit(),
+                Commands::Datagen(datagen_args) => self.datagen(datagen_args),
                 Commands::Go(go_args) => self.go(go_args),
                 Commands::Position(position_args) => self.position(position_args),
                 Commands::Query(query_args) => self.query(query_args),
                 Commands::Setoption(setoption_args) => self.setoption(setoption_args),
+                Commands::Suite(suite_args) => self.suite(suite_args),
             },
             Err(e) => {
                 print_error_trace(&if command.is_empty() {
@@ -359,6 +658,79 @@ impl UgiEngine {
     }
 }
 
+/// Formats a search information event as UGI `info` lines.
+fn info_to_lines(board: &Board, info: &SearchInfo) -> Vec<String> {
+    match info {
+        &SearchInfo::Iteration {
+            depth,
+            time,
+            score,
+            bound,
+            action,
+            nodes,
+        } => {
+            let mut line = format!(
+                "info depth {depth} time {} score {}",
+                time.as_millis(),
+                score_to_string(score)
+            );
+            match bound {
+                Some(ScoreBound::Lower) => line.push_str(" lowerbound"),
+                Some(ScoreBound::Upper) => line.push_str(" upperbound"),
+                None => (),
+            }
+            line.push_str(&format!(" pv {}", action_to_string(board, action)));
+            if let Some(nodes) = nodes {
+                let nps = nodes as u128 * 1_000_000_000 / time.as_nanos().max(1);
+                line.push_str(&format!(" nodes {nodes} nps {nps}"));
+            }
+            vec![line]
+        }
+        &SearchInfo::Book {
+            depth,
+            score,
+            action,
+        } => vec![format!(
+            "info book depth {depth} score {score} pv {}",
+            action_to_string(board, action)
+        )],
+        &SearchInfo::Skill {
+            level,
+            depth,
+            score,
+            action,
+        } => vec![format!(
+            "info skill level {level} depth {depth} score {} pv {}",
+            score_to_string(score),
+            action_to_string(board, action)
+        )],
+        SearchInfo::Stats(stats) => stats_to_lines(stats),
+    }
+}
+
+/// Formats the statistics of a search as UGI `info stats` lines.
+fn stats_to_lines(stats: &StatsSummary) -> Vec<String> {
+    let mut lines = vec![format!(
+        "info stats nodes {} qnodes {} tt-probes {} tt-hits {} tt-cutoffs {} beta-cutoffs {} first-move-cutoffs {} ({:.1}%) pvs-researches {}",
+        stats.nodes,
+        stats.qnodes,
+        stats.tt_probes,
+        stats.tt_hits,
+        stats.tt_cutoffs,
+        stats.beta_cutoffs,
+        stats.first_move_cutoffs,
+        stats.first_move_cutoff_rate() * 100.,
+        stats.pvs_researches,
+    )];
+    for &(depth, nodes, ebf) in &stats.iterations {
+        lines.push(match ebf {
+            Some(ebf) => format!("info stats depth {depth} nodes {nodes} ebf {ebf:.2}"),
+            None => format!("info stats depth {depth} nodes {nodes}"),
+        });
+    }
+    lines
+}
+
 /// Utility function to print an error's traceback.
 fn print_error_trace(error: &dyn std::error::Error) {
     let trace = get_error_trace(error);
@@ -386,9 +758,20 @@ fn play_actions(board: &mut Game, actions: &[String]) {
 }
 
 /// Sets the state of the board using PSN/FEN arguments
+///
+/// The position is validated unless unusual positions are allowed in the options.
 fn set_fen(board: &mut Game, fen_args: &FenArgs) {
     let fen: &str = fen_args.fen.as_ref();
-    let new_board: Result<Board, ParseError> = Board::try_from_fen(fen);
+    let allow_unusual_positions = board.options.allow_unusual_positions;
+    let new_board: Result<Board, ParseError> = Board::try_from_fen(fen).and_then(|new_board| {
+        if !allow_unusual_positions {
+            new_board.validate().map_err(|kind| ParseError {
+                kind: ParseErrorKind::InvalidPosition(kind),
+                value: fen.to_owned(),
+            })?;
+        }
+        Ok(new_board)
+    });
     let player = string_to_player(&fen_args.player);
     match (new_board, player) {
         (Ok(new_board), Ok(player)) => {
@@ -1,6 +1,8 @@
 //! This module implements various helper functions.
 
 use crate::errors::{ParseError, ParseErrorKind};
+use crate::search::alphabeta::SearchMode;
+use crate::search::eval::EvalTerm;
 
 /// Returns a vector of sorted indices
 pub fn argsort<T: Ord>(data: &[T], reverse: bool) -> Vec<usize> {
@@ -36,3 +38,25 @@ pub fn parse_bool_arg(argument: &str) -> Result<bool, ParseError> {
         })
     }
 }
+
+/// Parses evaluation term arguments in string format (see [`EvalTerm::name`]). Returns an error if the value is not a known term.
+pub fn parse_eval_term(argument: &str) -> Result<EvalTerm, ParseError> {
+    EvalTerm::ALL
+        .into_iter()
+        .find(|term| term.name() == argument)
+        .ok_or(ParseError {
+            kind: ParseErrorKind::InvalidEvalTerm,
+            value: argument.to_owned(),
+        })
+}
+
+/// Parses search mode arguments in string format (see [`SearchMode::name`]). Returns an error if the value is not a known mode.
+pub fn parse_search_mode(argument: &str) -> Result<SearchMode, ParseError> {
+    SearchMode::ALL
+        .into_iter()
+        .find(|mode| mode.name() == argument)
+        .ok_or(ParseError {
+            kind: ParseErrorKind::InvalidSearchMode,
+            value: argument.to_owned(),
+        })
+}
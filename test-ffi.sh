@@ -0,0 +1,7 @@
+#!/bin/bash -eu
+
+# Builds the C ABI and runs the C test program against it
+cargo build --release --lib
+mkdir -p target/ffi
+cc -Wall -Wextra -Werror -Iinclude tests/ffi/test_ffi.c -Ltarget/release -lpijersi_rs -o target/ffi/test_ffi
+LD_LIBRARY_PATH=target/release target/ffi/test_ffi
@@ -0,0 +1,126 @@
+use std::ffi::{c_char, CStr, CString};
+
+use pijersi_rs::ffi::*;
+
+/// Reads the NUL-terminated string written to a buffer
+fn buffer_to_string(buffer: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn test_ffi_game() {
+    let mut buffer: [c_char; 4096] = [0; 4096];
+    unsafe {
+        let engine = pijersi_engine_new();
+        assert!(!engine.is_null());
+        let mut required_size = 0;
+        assert_eq!(
+            pijersi_engine_legal_actions(engine, buffer.as_mut_ptr(), 16, &mut required_size),
+            PijersiErrorCode::BufferTooSmall
+        );
+        assert!(required_size > 16);
+        let mut actions_buffer: Vec<c_char> = vec![0; required_size];
+        assert_eq!(
+            pijersi_engine_legal_actions(
+                engine,
+                actions_buffer.as_mut_ptr(),
+                required_size,
+                std::ptr::null_mut()
+            ),
+            PijersiErrorCode::Ok
+        );
+        let actions = buffer_to_string(&actions_buffer);
+        assert_eq!(actions.len() + 1, required_size);
+        assert!(actions.split(' ').any(|action| action == "a5b5d4"));
+
+        let action = CString::new("a5b5d4").unwrap();
+        assert_eq!(
+            pijersi_engine_play(engine, action.as_ptr()),
+            PijersiErrorCode::Ok
+        );
+        assert_eq!(
+            pijersi_engine_play(engine, action.as_ptr()),
+            PijersiErrorCode::Rules
+        );
+        assert_eq!(
+            pijersi_engine_play(engine, std::ptr::null()),
+            PijersiErrorCode::NullPointer
+        );
+
+        let mut score = 0;
+        assert_eq!(
+            pijersi_engine_search(engine, 0, 0, buffer.as_mut_ptr(), buffer.len(), &mut score),
+            PijersiErrorCode::InvalidArgument
+        );
+        assert_eq!(
+            pijersi_engine_search(engine, 2, 0, buffer.as_mut_ptr(), 2, &mut score),
+            PijersiErrorCode::BufferTooSmall
+        );
+        assert_eq!(
+            pijersi_engine_search(engine, 2, 0, buffer.as_mut_ptr(), buffer.len(), &mut score),
+            PijersiErrorCode::Ok
+        );
+        assert_eq!(
+            pijersi_engine_play(engine, buffer.as_ptr()),
+            PijersiErrorCode::Ok
+        );
+
+        // The first player wins by moving a scissors to the last row
+        let psn = CString::new("6/S-6/6/p-6/6/7/6 w 0 1").unwrap();
+        assert_eq!(
+            pijersi_engine_set_position(engine, psn.as_ptr()),
+            PijersiErrorCode::Ok
+        );
+        assert!(!pijersi_engine_is_win(engine));
+        let action = CString::new("f1g1").unwrap();
+        assert_eq!(
+            pijersi_engine_play(engine, action.as_ptr()),
+            PijersiErrorCode::Ok
+        );
+        assert!(pijersi_engine_is_win(engine));
+        assert!(!pijersi_engine_is_draw(engine));
+        assert_eq!(pijersi_engine_winner(engine), 0);
+        assert_eq!(
+            pijersi_engine_get_position(engine, buffer.as_mut_ptr(), buffer.len()),
+            PijersiErrorCode::Ok
+        );
+        assert_eq!(buffer_to_string(&buffer), "S-5/7/6/p-6/6/7/6 b 1 1");
+
+        // A position that is already won is rejected
+        let psn = CString::new("S-5/7/6/p-6/6/7/6 b 0 1").unwrap();
+        assert_eq!(
+            pijersi_engine_set_position(engine, psn.as_ptr()),
+            PijersiErrorCode::Parse
+        );
+
+        let invalid = CString::new("invalid").unwrap();
+        assert_eq!(
+            pijersi_engine_set_position(engine, invalid.as_ptr()),
+            PijersiErrorCode::Parse
+        );
+        pijersi_engine_free(engine);
+    }
+    assert!(!unsafe { pijersi_engine_is_win(std::ptr::null()) });
+    assert_eq!(unsafe { pijersi_engine_winner(std::ptr::null()) }, -1);
+}
+
+#[test]
+fn test_ffi_header() {
+    let header = include_str!("../../include/pijersi.h");
+    let source = include_str!("../../src/ffi.rs");
+    let exported: Vec<&str> = source
+        .lines()
+        .filter_map(|line| line.split("extern \"C\" fn ").nth(1))
+        .map(|line| line.split('(').next().unwrap())
+        .collect();
+    assert_eq!(exported.len(), 10);
+    for function in exported {
+        assert!(
+            header.contains(&format!(" {function}(")) || header.contains(&format!("*{function}(")),
+            "{function} is not declared in the header"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+/* Exercises the C ABI of the engine, run it with ./test-ffi.sh */
+
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "pijersi.h"
+
+static const char *START_PSN =
+    "s-p-r-s-p-r-/p-r-s-wwr-s-p-/6/7/6/P-S-R-WWS-R-P-/R-P-S-R-P-S- w 0 1";
+
+int main(void) {
+    char buffer[4096];
+    int16_t score = 0;
+
+    PijersiEngine *engine = pijersi_engine_new();
+    assert(engine != NULL);
+
+    assert(pijersi_engine_get_position(engine, buffer, sizeof(buffer)) == PIJERSI_OK);
+    assert(strcmp(buffer, START_PSN) == 0);
+    assert(pijersi_engine_get_position(engine, buffer, 4) == PIJERSI_BUFFER_TOO_SMALL);
+
+    assert(pijersi_engine_legal_actions(engine, buffer, sizeof(buffer)) == PIJERSI_OK);
+    assert(strstr(buffer, "a5b5d4") != NULL);
+
+    assert(pijersi_engine_play(engine, "a5b5d4") == PIJERSI_OK);
+    assert(pijersi_engine_play(engine, "a5b5d4") == PIJERSI_RULES);
+    assert(pijersi_engine_play(engine, "z9") == PIJERSI_PARSE);
+    assert(pijersi_engine_play(engine, NULL) == PIJERSI_NULL_POINTER);
+
+    assert(!pijersi_engine_is_win(engine));
+    assert(!pijersi_engine_is_draw(engine));
+    assert(pijersi_engine_winner(engine) == -1);
+
+    assert(pijersi_engine_search(engine, 0, 0, buffer, sizeof(buffer), &score) == PIJERSI_INVALID_ARGUMENT);
+    assert(pijersi_engine_search(engine, 3, 0, buffer, sizeof(buffer), &score) == PIJERSI_OK);
+    assert(strlen(buffer) == 4 || strlen(buffer) == 6);
+    assert(pijersi_engine_play(engine, buffer) == PIJERSI_OK);
+    assert(pijersi_engine_search(engine, 0, 100, buffer, sizeof(buffer), NULL) == PIJERSI_OK);
+
+    assert(pijersi_engine_set_position(engine, "invalid") == PIJERSI_PARSE);
+    assert(pijersi_engine_set_position(engine, START_PSN) == PIJERSI_OK);
+
+    pijersi_engine_free(engine);
+    pijersi_engine_free(NULL);
+
+    printf("test_ffi: ok\n");
+    return 0;
+}
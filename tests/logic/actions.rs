@@ -1,4 +1,13 @@
-use pijersi_rs::bitboard::Board;
+use pijersi_rs::{
+    bitboard::Board,
+    logic::{
+        actions::{ActionStep, StepKind},
+        moves::MoveKind,
+        translate::{action_to_string_with_captures, string_to_action, string_to_index},
+    },
+    piece::PieceTrait,
+    piece::{BLACK_SCISSORS, WHITE_PAPER, WHITE_ROCK},
+};
 
 use crate::TEST_BOARD_STR;
 
@@ -21,3 +30,193 @@ fn test_play_action() {
         assert_eq!(board.to_string(), output);
     }
 }
+
+/// White stack of rock on paper on c1, white paper on c2, white rock on c3, black scissors on d2
+const CAPTURE_BOARD_FEN: &str = "6/7/6/1s-5/PRP-R-3/7/6";
+
+fn step(kind: StepKind, from: &str, to: &str, piece: u8, captured: Option<u8>) -> ActionStep {
+    ActionStep {
+        kind,
+        from: string_to_index(from).unwrap(),
+        to: string_to_index(to).unwrap(),
+        piece,
+        captured,
+    }
+}
+
+#[test]
+fn test_describe_action() {
+    let board = Board::try_from_fen(CAPTURE_BOARD_FEN).unwrap();
+    let rock_on_paper = WHITE_ROCK.stack_on(WHITE_PAPER);
+    let describe = |action_string: &str| {
+        board.describe_action(string_to_action(&board, action_string).unwrap())
+    };
+
+    let description = describe("c1d2d2");
+    assert_eq!(description.kind, MoveKind::Move);
+    assert_eq!(
+        description.steps,
+        vec![step(
+            StepKind::Move,
+            "c1",
+            "d2",
+            rock_on_paper,
+            Some(BLACK_SCISSORS)
+        )]
+    );
+    assert_eq!(description.captures(), vec![BLACK_SCISSORS]);
+    assert!(!description.is_win);
+
+    let description = describe("c2c3");
+    assert_eq!(description.kind, MoveKind::Stack);
+    assert_eq!(
+        description.steps,
+        vec![step(StepKind::Stack, "c2", "c3", WHITE_PAPER, None)]
+    );
+    assert!(!description.is_capture());
+
+    let description = describe("c1c1d2");
+    assert_eq!(description.kind, MoveKind::Unstack);
+    assert_eq!(
+        description.steps,
+        vec![step(
+            StepKind::Unstack,
+            "c1",
+            "d2",
+            WHITE_ROCK,
+            Some(BLACK_SCISSORS)
+        )]
+    );
+
+    let description = describe("c3c2d2");
+    assert_eq!(description.kind, MoveKind::StackMove);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Stack, "c3", "c2", WHITE_ROCK, None),
+            step(
+                StepKind::Move,
+                "c2",
+                "d2",
+                rock_on_paper,
+                Some(BLACK_SCISSORS)
+            ),
+        ]
+    );
+
+    let description = describe("c1d1d2");
+    assert_eq!(description.kind, MoveKind::MoveUnstack);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Move, "c1", "d1", rock_on_paper, None),
+            step(
+                StepKind::Unstack,
+                "d1",
+                "d2",
+                WHITE_ROCK,
+                Some(BLACK_SCISSORS)
+            ),
+        ]
+    );
+
+    let description = describe("c1d1c2");
+    assert_eq!(description.kind, MoveKind::MoveStack);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Move, "c1", "d1", rock_on_paper, None),
+            step(StepKind::Stack, "d1", "c2", WHITE_ROCK, None),
+        ]
+    );
+
+    // Actions ending on their start cell, which is empty after the first step
+    let description = describe("c1d1c1");
+    assert_eq!(description.kind, MoveKind::MoveUnstack);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Move, "c1", "d1", rock_on_paper, None),
+            step(StepKind::Unstack, "d1", "c1", WHITE_ROCK, None),
+        ]
+    );
+
+    let description = describe("c1d2c1");
+    assert_eq!(description.kind, MoveKind::MoveUnstack);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(
+                StepKind::Move,
+                "c1",
+                "d2",
+                rock_on_paper,
+                Some(BLACK_SCISSORS)
+            ),
+            step(StepKind::Unstack, "d2", "c1", WHITE_ROCK, None),
+        ]
+    );
+
+    let description = describe("c3c2c3");
+    assert_eq!(description.kind, MoveKind::StackMove);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Stack, "c3", "c2", WHITE_ROCK, None),
+            step(StepKind::Move, "c2", "c3", rock_on_paper, None),
+        ]
+    );
+
+    let description = describe("c2c3c2");
+    assert_eq!(description.kind, MoveKind::StackMove);
+    assert_eq!(
+        description.steps,
+        vec![
+            step(StepKind::Stack, "c2", "c3", WHITE_PAPER, None),
+            step(
+                StepKind::Move,
+                "c3",
+                "c2",
+                WHITE_PAPER.stack_on(WHITE_ROCK),
+                None
+            ),
+        ]
+    );
+
+    let board = Board::try_from_fen("6/R-6/6/7/6/7/6").unwrap();
+    assert!(
+        board
+            .describe_action(string_to_action(&board, "f1g1").unwrap())
+            .is_win
+    );
+}
+
+#[test]
+fn test_action_to_string_with_captures() {
+    let board = Board::try_from_fen(CAPTURE_BOARD_FEN).unwrap();
+    for (action_string, marked_string) in [
+        ("c1d2d2", "c1d2xd2"),
+        ("c3d2", "c3xd2"),
+        ("c2c3", "c2c3"),
+        ("c1c1d2", "c1c1xd2"),
+        ("c3c2d2", "c3c2xd2"),
+        ("c1d1d2", "c1d1xd2"),
+        ("c1d1c1", "c1d1c1"),
+        ("c1d2c1", "c1xd2c1"),
+        ("c3c2c3", "c3c2c3"),
+        ("c2c3c2", "c2c3c2"),
+    ] {
+        let action = string_to_action(&board, action_string).unwrap();
+        assert_eq!(
+            action_to_string_with_captures(&board, action),
+            marked_string
+        );
+        assert_eq!(string_to_action(&board, marked_string).unwrap(), action);
+    }
+
+    // Captures on both steps
+    let board = Board::try_from_fen("6/7/6/s-s-5/PRP-R-3/7/6").unwrap();
+    let action = string_to_action(&board, "c1d1d2").unwrap();
+    assert_eq!(board.describe_action(action).captures().len(), 2);
+    assert_eq!(action_to_string_with_captures(&board, action), "c1xd1xd2");
+}
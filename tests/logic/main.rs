@@ -1,7 +1,12 @@
 mod actions;
 mod movegen;
+mod moves;
+mod narration;
 mod perft;
+mod random;
+mod reference;
 mod rules;
+mod symmetry;
 mod translate;
 
 /// Cells state for testing
@@ -62,3 +62,33 @@ fn test_available_piece_actions() {
     let output_set: HashSet<Action> = TEST_AVAILABLE_PIECE_ACTIONS.iter().cloned().collect();
     assert_eq!(input_set, output_set)
 }
+
+#[test]
+fn test_available_captures_and_wins_last_row() {
+    // Regression: the captures and wins listed moves onto occupied cells of the last row (illegal) and missed the stacks onto an ally on the last row
+    let boards = [
+        // The last row is blocked by a white paper on top of a stack
+        "s-1r-s-rp1/p-1s-wwr-1sp/6/p-5RS/r-2W-W-1/P-1R-2SP1/R-SPS-R-P-1",
+        // Stacking onto a wise on the last row wins
+        "6/7/6/7/6/2r-4/1w-4",
+    ];
+    for fen in boards {
+        let board = Board::try_from_fen(fen).unwrap();
+        for player in 0..2 {
+            let actions: HashSet<Action> =
+                board.available_player_actions(player).into_iter().collect();
+            let captures_and_wins: HashSet<Action> = board
+                .available_player_captures_and_wins(player)
+                .into_iter()
+                .collect();
+            assert!(captures_and_wins.is_subset(&actions), "{fen} {player}");
+            assert!(
+                actions
+                    .iter()
+                    .filter(|&&action| board.is_action_win(action, player))
+                    .all(|action| captures_and_wins.contains(action)),
+                "{fen} {player}"
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use pijersi_rs::{
+    bitboard::Board,
+    logic::{
+        actions::{Action, ActionTrait},
+        index::INDEX_NULL,
+        moves::{Move, MoveKind},
+        translate::{action_to_string, string_to_action},
+    },
+};
+
+#[test]
+fn test_move_accessors() {
+    let stack_move = Move::new(4, Some(11), 23);
+    assert_eq!(stack_move.start(), 4);
+    assert_eq!(stack_move.mid(), Some(11));
+    assert_eq!(stack_move.end(), 23);
+    assert_eq!(Action::from(stack_move), Action::from_indices(4, 11, 23));
+
+    let simple_move = Move::new(4, None, 11);
+    assert_eq!(simple_move.mid(), None);
+    assert_eq!(
+        simple_move.action(),
+        Action::from_indices(4, INDEX_NULL, 11)
+    );
+
+    // The additional data of the action is dropped
+    let action = Action::from_indices(4, 11, 23) | (5 << 24);
+    assert_eq!(action.search_depth(), 5);
+    assert_eq!(Move::from(action), stack_move);
+}
+
+#[test]
+fn test_move_notation() {
+    let mut board = Board::EMPTY;
+    board.init();
+    for action in board.available_player_actions(0) {
+        let new_move = Move::from(action);
+        let move_string = new_move.to_string();
+        assert_eq!(move_string.len(), 6);
+        assert_eq!(move_string.parse::<Move>().unwrap(), new_move);
+        // The notation is also understood by the UGI parser
+        assert_eq!(string_to_action(&board, &move_string).unwrap(), action);
+    }
+
+    assert_eq!("a5b5d4".parse::<Move>().unwrap().to_string(), "a5b5d4");
+    assert_eq!("b4c4c4".parse::<Move>().unwrap().mid(), None);
+    assert!("a5b5".parse::<Move>().is_err());
+    assert!("a5b5z4".parse::<Move>().is_err());
+    assert!("a5b5d9".parse::<Move>().is_err());
+    assert!("a5b5d4a".parse::<Move>().is_err());
+}
+
+#[test]
+fn test_move_kind() {
+    // White stack of rock on paper on c1, white paper on c2, white rock on c3, black scissors on d2
+    let board = Board::try_from_fen("6/7/6/1s-5/PRP-R-3/7/6").unwrap();
+    let kind = |move_string: &str| move_string.parse::<Move>().unwrap().kind(&board);
+    assert_eq!(kind("c1d2d2"), MoveKind::Move);
+    assert_eq!(kind("c2c2c3"), MoveKind::Stack);
+    assert_eq!(kind("c1c1d2"), MoveKind::Unstack);
+    assert_eq!(kind("c3c2d2"), MoveKind::StackMove);
+    assert_eq!(kind("c1d1c2"), MoveKind::MoveStack);
+    assert_eq!(kind("c1d1d2"), MoveKind::MoveUnstack);
+    // The start cell is empty after the first move
+    assert_eq!(kind("c1d1c1"), MoveKind::MoveUnstack);
+    assert_eq!(kind("c1d2c1"), MoveKind::MoveUnstack);
+    assert_eq!(kind("c3c2c3"), MoveKind::StackMove);
+    assert_eq!(
+        action_to_string(&board, "c1c1d2".parse::<Move>().unwrap().action()),
+        "c1c1d2"
+    );
+}
+
+#[test]
+fn test_move_serde() {
+    let new_move: Move = "a5b5d4".parse().unwrap();
+    assert_eq!(serde_json::to_string(&new_move).unwrap(), "\"a5b5d4\"");
+    assert_eq!(
+        serde_json::from_str::<Move>("\"a5b5d4\"").unwrap(),
+        new_move
+    );
+    assert!(serde_json::from_str::<Move>("\"a5b5\"").is_err());
+
+    let config = bincode::config::standard();
+    let bytes = bincode::serde::encode_to_vec(new_move, config).unwrap();
+    let (decoded, _): (Move, usize) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+    assert_eq!(decoded, new_move);
+    let bytes = bincode::serde::encode_to_vec(Action::from_indices(50, 1, 2), config).unwrap();
+    assert!(bincode::serde::decode_from_slice::<Move, _>(&bytes, config).is_err());
+}
@@ -0,0 +1,90 @@
+use pijersi_rs::{
+    bitboard::Board,
+    logic::{
+        narration::{describe_cells, describe_pieces, narrate_action},
+        translate::string_to_action,
+    },
+};
+
+/// White stack of rock on paper on c1, white paper on c2, white rock on c3, black scissors on d2
+const NARRATION_BOARD_FEN: &str = "6/7/6/1s-5/PRP-R-3/7/6";
+
+fn narrate(board: &Board, action_string: &str) -> String {
+    narrate_action(board, string_to_action(board, action_string).unwrap())
+}
+
+#[test]
+fn test_narrate_action() {
+    let board = Board::try_from_fen(NARRATION_BOARD_FEN).unwrap();
+    assert_eq!(
+        narrate(&board, "c1d2"),
+        "White stack of rock on paper on c1 moves to d2, capturing black scissors."
+    );
+    assert_eq!(
+        narrate(&board, "c1c1d2"),
+        "White rock from the stack on c1 unstacks to d2, capturing black scissors."
+    );
+    assert_eq!(
+        narrate(&board, "c2c3"),
+        "White paper on c2 stacks onto rock on c3."
+    );
+    assert_eq!(
+        narrate(&board, "c3c2d2"),
+        "White rock on c3 stacks onto paper on c2, then the stack moves to d2, capturing black scissors."
+    );
+    assert_eq!(
+        narrate(&board, "c1d1d2"),
+        "White stack of rock on paper on c1 moves to d1, then its rock unstacks to d2, capturing black scissors."
+    );
+
+    // Actions ending on their start cell, which is empty after the first step
+    assert_eq!(
+        narrate(&board, "c1d1c1"),
+        "White stack of rock on paper on c1 moves to d1, then its rock unstacks to c1."
+    );
+    assert_eq!(
+        narrate(&board, "c1d2c1"),
+        "White stack of rock on paper on c1 moves to d2, capturing black scissors, then its rock unstacks to c1."
+    );
+    assert_eq!(
+        narrate(&board, "c3c2c3"),
+        "White rock on c3 stacks onto paper on c2, then the stack moves to c3."
+    );
+    assert_eq!(
+        narrate(&board, "c2c3c2"),
+        "White paper on c2 stacks onto rock on c3, then the stack moves to c2."
+    );
+
+    let board = Board::try_from_fen("6/R-6/6/7/6/7/6").unwrap();
+    assert_eq!(
+        narrate(&board, "f1g1"),
+        "White rock on f1 moves to g1, reaching the last row: white wins."
+    );
+}
+
+#[test]
+fn test_describe_position() {
+    let board = Board::try_from_fen(NARRATION_BOARD_FEN).unwrap();
+    assert_eq!(
+        describe_cells(&board),
+        [
+            "Row g: empty.",
+            "Row f: empty.",
+            "Row e: empty.",
+            "Row d: d2 black scissors.",
+            "Row c: c1 white stack of rock on paper, c2 white paper, c3 white rock.",
+            "Row b: empty.",
+            "Row a: empty.",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        describe_pieces(&board),
+        "White: paper on c2; rock on c3; stack of rock on paper on c1.\nBlack: scissors on d2."
+    );
+
+    let mut start_board = Board::EMPTY;
+    start_board.init();
+    assert!(describe_pieces(&start_board)
+        .starts_with("White: scissors on b2, b5, a3, a6; paper on b1, b7, a2, a5;"));
+}
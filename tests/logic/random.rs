@@ -0,0 +1,79 @@
+use pijersi_rs::logic::random::{
+    material_balance, GenerationMethod, GeneratorOptions, PositionGenerator,
+};
+
+#[test]
+fn test_generator_is_seeded() {
+    for method in [
+        GenerationMethod::Playout { plies: 10 },
+        GenerationMethod::Placement { max_pieces: 8 },
+    ] {
+        let options = GeneratorOptions {
+            method,
+            ..GeneratorOptions::default()
+        };
+        let positions: Vec<_> = PositionGenerator::new(1, options.clone())
+            .take(10)
+            .collect();
+        assert_eq!(positions.len(), 10);
+        assert_eq!(
+            PositionGenerator::new(1, options.clone())
+                .take(10)
+                .collect::<Vec<_>>(),
+            positions
+        );
+        assert_ne!(
+            PositionGenerator::new(2, options)
+                .take(10)
+                .collect::<Vec<_>>(),
+            positions
+        );
+    }
+}
+
+#[test]
+fn test_generator_playout() {
+    let options = GeneratorOptions {
+        method: GenerationMethod::Playout { plies: 15 },
+        ..GeneratorOptions::default()
+    };
+    for (board, player) in PositionGenerator::new(0, options).take(50) {
+        assert!(board.validate().is_ok());
+        assert!(!board.available_player_actions(player).is_empty());
+    }
+
+    // The player to move after a playout is given by the number of plies
+    let options = GeneratorOptions {
+        method: GenerationMethod::Playout { plies: 2 },
+        player: Some(1),
+        ..GeneratorOptions::default()
+    };
+    assert!(PositionGenerator::new(0, options).generate().is_none());
+}
+
+#[test]
+fn test_generator_placement() {
+    let options = GeneratorOptions {
+        method: GenerationMethod::Placement { max_pieces: 10 },
+        player: Some(1),
+        material_balance: Some(1..=3),
+        ..GeneratorOptions::default()
+    };
+    for (board, player) in PositionGenerator::new(0, options).take(50) {
+        assert!(board.validate().is_ok());
+        assert_eq!(player, 1);
+        assert!((1..=3).contains(&material_balance(&board)));
+        let white_pieces = (board.count_pieces() as i64 + material_balance(&board)) / 2;
+        assert!((1..=10).contains(&white_pieces));
+    }
+
+    // Won positions are only generated if they are allowed
+    let options = GeneratorOptions {
+        method: GenerationMethod::Placement { max_pieces: 14 },
+        allow_won: true,
+        ..GeneratorOptions::default()
+    };
+    assert!(PositionGenerator::new(0, options)
+        .take(200)
+        .any(|(board, _player)| board.get_winner().is_some()));
+}
@@ -0,0 +1,369 @@
+//! A slow reference move generator on a plain `[Piece; 45]` array, and a fuzz test comparing it to the engine.
+//!
+//! The reference does not use the lookup tables: the cells are located with doubled coordinates (the column is doubled and shifted by one on the rows of 6 cells) and every step of an action is played on a copy of the cells before generating the next one.
+
+use pijersi_rs::{
+    bitboard::Board,
+    logic::{
+        actions::{Action, ActionTrait},
+        index::{CellIndex, INDEX_NULL},
+        random::{GenerationMethod, GeneratorOptions, PositionGenerator},
+        Player, N_CELLS,
+    },
+    piece::{Piece, PieceTrait, CELL_EMPTY},
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+type Cells = [Piece; N_CELLS];
+
+/// The number of cells of each row, from the top row (black's side) to the bottom row (white's side)
+const ROW_LENGTHS: [usize; 7] = [6, 7, 6, 7, 6, 7, 6];
+
+/// The 6 directions in doubled coordinates (row, column)
+const DIRECTIONS: [(i64, i64); 6] = [(0, 2), (0, -2), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// The number of random games played from each kind of starting position
+const N_GAMES: u64 = 1000;
+/// The maximum number of plies of a random game
+const MAX_PLIES: usize = 100;
+
+/// Returns the doubled coordinates (row, column) of a cell.
+fn coordinates(index: CellIndex) -> (i64, i64) {
+    let mut first = 0;
+    for (row, &length) in ROW_LENGTHS.iter().enumerate() {
+        if index < first + length {
+            let shift = if length == 6 { 1 } else { 0 };
+            return (row as i64, 2 * (index - first) as i64 + shift);
+        }
+        first += length;
+    }
+    unreachable!()
+}
+
+/// Returns the cell at the given doubled coordinates (if it exists).
+fn cell(row: i64, column: i64) -> Option<CellIndex> {
+    if !(0..7).contains(&row) {
+        return None;
+    }
+    let length = ROW_LENGTHS[row as usize];
+    let shift = if length == 6 { 1 } else { 0 };
+    if column < shift || (column - shift) % 2 != 0 || (column - shift) / 2 >= length as i64 {
+        return None;
+    }
+    let first: usize = ROW_LENGTHS[..row as usize].iter().sum();
+    Some(first + ((column - shift) / 2) as usize)
+}
+
+/// Returns the cell at the given distance in a direction (if it exists).
+fn step(index: CellIndex, (d_row, d_column): (i64, i64), distance: i64) -> Option<CellIndex> {
+    let (row, column) = coordinates(index);
+    cell(row + distance * d_row, column + distance * d_column)
+}
+
+/// Returns the colour of a player's pieces.
+fn colour(player: Player) -> Piece {
+    player << 2
+}
+
+/// Returns true if the first piece type beats the second one: scissors cut paper, paper wraps rock and rock crushes scissors.
+fn beats(attacker: Piece, defender: Piece) -> bool {
+    let (attacker, defender) = (attacker.r#type(), defender.r#type());
+    !attacker.is_wise() && !defender.is_wise() && (attacker + 1) % 3 == defender
+}
+
+/// Returns true if a piece (or stack) can move to a cell: it must be empty or hold an enemy whose top it beats.
+fn can_enter(piece: Piece, target: Piece) -> bool {
+    target.is_empty() || (target.colour() != piece.colour() && beats(piece.top(), target.top()))
+}
+
+/// Returns true if a piece can stack onto a cell: it must hold a single ally, and only a wise can stack onto a wise.
+fn can_stack(piece: Piece, target: Piece) -> bool {
+    !target.is_empty()
+        && !target.is_stack()
+        && target.colour() == piece.colour()
+        && (!piece.top().is_wise() || target.is_wise())
+}
+
+/// Returns true if a piece reaching the cell wins: it is not a wise and the cell is on the opponent's home row.
+fn is_winning_step(piece: Piece, index: CellIndex) -> bool {
+    let (row, _) = coordinates(index);
+    let home_row = if piece.colour() == colour(0) { 0 } else { 6 };
+    !piece.top().is_wise() && row == home_row
+}
+
+/// A reference action: its encoding, the cells after it is played and whether it wins.
+struct ReferenceAction {
+    action: Action,
+    cells: Cells,
+    is_win: bool,
+}
+
+/// Moves the whole piece or stack.
+fn play_move(cells: &Cells, from: CellIndex, to: CellIndex) -> Cells {
+    let mut new_cells = *cells;
+    new_cells[to] = cells[from];
+    new_cells[from] = CELL_EMPTY;
+    new_cells
+}
+
+/// Moves the top piece onto an ally (stack) or to a cell it can enter (unstack).
+fn play_top(cells: &Cells, from: CellIndex, to: CellIndex) -> Cells {
+    let mut new_cells = *cells;
+    let top = cells[from].top();
+    new_cells[from] = if cells[from].is_stack() {
+        cells[from].bottom()
+    } else {
+        CELL_EMPTY
+    };
+    new_cells[to] = if can_stack(top, cells[to]) {
+        top.stack_on(cells[to])
+    } else {
+        top
+    };
+    new_cells
+}
+
+/// Returns the 1-range and 2-range moves of the stack at the given index (2-range moves need an empty cell in between).
+fn stack_moves(cells: &Cells, index: CellIndex) -> Vec<CellIndex> {
+    let mut moves = vec![];
+    for direction in DIRECTIONS {
+        if let Some(target) = step(index, direction, 1) {
+            if can_enter(cells[index], cells[target]) {
+                moves.push(target);
+            }
+            if cells[target].is_empty() {
+                if let Some(target) = step(index, direction, 2) {
+                    if can_enter(cells[index], cells[target]) {
+                        moves.push(target);
+                    }
+                }
+            }
+        }
+    }
+    moves
+}
+
+/// Returns the neighbours of a cell.
+fn neighbours(index: CellIndex) -> Vec<CellIndex> {
+    DIRECTIONS
+        .iter()
+        .filter_map(|&direction| step(index, direction, 1))
+        .collect()
+}
+
+/// Generates the actions of a player with the cells they lead to.
+///
+/// * A single piece can move by 1, stack onto an ally, or stack onto an ally then move the new stack by 1 or 2.
+/// * A stack can move by 1 or 2, unstack its top piece, stack its top piece onto an ally, move by 1 or 2 then unstack or stack its top piece, or stack its top piece onto an ally then move the new stack by 1 or 2.
+fn reference_actions(cells: &Cells, player: Player) -> Vec<ReferenceAction> {
+    let mut actions = vec![];
+    let mut push = |action: Action, new_cells: Cells, is_win: bool| {
+        actions.push(ReferenceAction {
+            action,
+            cells: new_cells,
+            is_win,
+        })
+    };
+    for start in 0..N_CELLS {
+        let piece = cells[start];
+        if piece.is_empty() || piece.colour() != colour(player) {
+            continue;
+        }
+        let top = piece.top();
+        if piece.is_stack() {
+            for mid in stack_moves(cells, start) {
+                let moved = play_move(cells, start, mid);
+                let is_win = is_winning_step(piece, mid);
+                push(Action::from_indices(start, INDEX_NULL, mid), moved, is_win);
+                for end in neighbours(mid) {
+                    if can_stack(top, moved[end]) || can_enter(top, moved[end]) {
+                        push(
+                            Action::from_indices(start, mid, end),
+                            play_top(&moved, mid, end),
+                            is_win || is_winning_step(top, end),
+                        );
+                    }
+                }
+            }
+        } else {
+            for mid in neighbours(start) {
+                if can_enter(piece, cells[mid]) {
+                    push(
+                        Action::from_indices(start, INDEX_NULL, mid),
+                        play_move(cells, start, mid),
+                        is_winning_step(piece, mid),
+                    );
+                }
+            }
+        }
+        for mid in neighbours(start) {
+            if piece.is_stack() && can_enter(top, cells[mid]) {
+                // Unstack
+                push(
+                    Action::from_indices(start, start, mid),
+                    play_top(cells, start, mid),
+                    is_winning_step(top, mid),
+                );
+            }
+            if can_stack(top, cells[mid]) {
+                // Stack, then optionally move the new stack
+                let stacked = play_top(cells, start, mid);
+                let is_win = is_winning_step(top, mid);
+                push(Action::from_indices(start, start, mid), stacked, is_win);
+                for end in stack_moves(&stacked, mid) {
+                    push(
+                        Action::from_indices(start, mid, end),
+                        play_move(&stacked, mid, end),
+                        is_win || is_winning_step(stacked[mid], end),
+                    );
+                }
+            }
+        }
+    }
+    actions
+}
+
+/// Returns the cells of a board.
+fn board_to_cells(board: &Board) -> Cells {
+    std::array::from_fn(|index| board.get_piece(index))
+}
+
+/// Returns the board of cells.
+fn cells_to_board(cells: &Cells) -> Board {
+    let mut board = Board::EMPTY;
+    for (index, &piece) in cells.iter().enumerate() {
+        if !piece.is_empty() {
+            board.set_piece(index, piece);
+        }
+    }
+    board
+}
+
+/// Compares the engine to the reference on a position and returns the reference actions.
+fn compare(cells: &Cells, player: Player) -> Vec<ReferenceAction> {
+    let board = cells_to_board(cells);
+    assert_eq!(board_to_cells(&board), *cells);
+    let position = board.to_fen();
+
+    let mut reference = reference_actions(cells, player);
+    reference.sort_by_key(|reference_action| reference_action.action);
+    let engine_actions = board.available_player_actions(player);
+    let mut engine_actions: Vec<Action> = engine_actions[..].to_vec();
+    engine_actions.sort_unstable();
+    let reference_encodings: Vec<Action> = reference.iter().map(|r| r.action).collect();
+    assert_eq!(engine_actions, reference_encodings, "{position} {player}");
+
+    for reference_action in &reference {
+        let action = reference_action.action;
+        let mut new_board = board;
+        new_board.play_action(action);
+        assert_eq!(
+            board_to_cells(&new_board),
+            reference_action.cells,
+            "{position} {player} {action:x}"
+        );
+        assert_eq!(
+            board.is_action_win(action, player),
+            reference_action.is_win,
+            "{position} {player} {action:x}"
+        );
+    }
+
+    // The captures and wins are the legal actions that capture or win
+    let count_pieces = |cells: &Cells| {
+        cells
+            .iter()
+            .map(|piece| piece.is_stack() as usize + !piece.is_empty() as usize)
+            .sum::<usize>()
+    };
+    let mut captures_and_wins: Vec<Action> = board
+        .available_player_captures_and_wins(player)
+        .into_iter()
+        .collect();
+    captures_and_wins.sort_unstable();
+    captures_and_wins.dedup();
+    let reference_captures_and_wins: Vec<Action> = reference
+        .iter()
+        .filter(|r| r.is_win || count_pieces(&r.cells) < count_pieces(cells))
+        .map(|r| r.action)
+        .collect();
+    assert_eq!(
+        captures_and_wins, reference_captures_and_wins,
+        "{position} {player}"
+    );
+    assert_eq!(
+        board.has_winning_action(player),
+        reference
+            .iter()
+            .any(|reference_action| reference_action.is_win),
+        "{position} {player}"
+    );
+
+    for capturing_player in [0, 1] {
+        let capturable: Vec<CellIndex> = (0..N_CELLS)
+            .filter(|&index| {
+                let piece = cells[index];
+                !piece.is_empty()
+                    && piece.colour() != colour(capturing_player)
+                    && !piece.top().is_wise()
+            })
+            .collect();
+        assert_eq!(
+            board
+                .capturable(capturing_player)
+                .collect::<Vec<CellIndex>>(),
+            capturable,
+            "{position} {capturing_player}"
+        );
+    }
+    reference
+}
+
+/// Plays a random game from a position, comparing the engine to the reference at every ply.
+fn play_random_game(cells: Cells, player: Player, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (mut cells, mut player) = (cells, player);
+    for _ in 0..MAX_PLIES {
+        let reference = compare(&cells, player);
+        if reference.is_empty() || cells_to_board(&cells).get_winner().is_some() {
+            break;
+        }
+        cells = reference[rng.random_range(0..reference.len())].cells;
+        player = 1 - player;
+    }
+}
+
+#[test]
+fn test_reference_geometry() {
+    for index in 0..N_CELLS {
+        let (row, column) = coordinates(index);
+        assert_eq!(cell(row, column), Some(index));
+    }
+    // The neighbours of the corners and of the center
+    assert_eq!(neighbours(0), vec![1, 7, 6]);
+    assert_eq!(neighbours(22).len(), 6);
+}
+
+#[test]
+fn test_fuzz_movegen_from_start() {
+    let mut board = Board::EMPTY;
+    board.init();
+    for seed in 0..N_GAMES {
+        play_random_game(board_to_cells(&board), 0, seed);
+    }
+}
+
+#[test]
+fn test_fuzz_movegen_from_placements() {
+    let options = GeneratorOptions {
+        method: GenerationMethod::Placement { max_pieces: 14 },
+        ..GeneratorOptions::default()
+    };
+    for (seed, (board, player)) in PositionGenerator::new(0, options)
+        .take(N_GAMES as usize)
+        .enumerate()
+    {
+        play_random_game(board_to_cells(&board), player, seed as u64);
+    }
+}
@@ -1,4 +1,10 @@
-use pijersi_rs::{bitboard::Board, logic::rules::is_action_legal};
+use pijersi_rs::{
+    bitboard::Board,
+    errors::InvalidPositionKind,
+    game::Game,
+    logic::rules::is_action_legal,
+    piece::{WHITE_PAPER, WHITE_WISE},
+};
 
 /// Cells state for testing
 /// startpos > a6b7 g5f5d6 b6a5b6 d6c6c6
@@ -21,6 +27,7 @@ const TEST_BOARD_2_STR: &str =
 ///  .  .  .  .  .  .
 /// P- S- R- WW PS R- SP
 ///  R- P- S- R- .  .
+#[allow(dead_code)]
 const TEST_BOARD_3_STR: &str =
     "s-p-r-s-..r-p-r-s-ww..s-p-......................pr..............P-S-R-WWPSR-SPR-P-S-R-....";
 
@@ -64,6 +71,26 @@ fn test_is_action_win() {
     assert!(test_board_2.is_action_win(2893087, 1));
 }
 
+#[test]
+fn test_has_winning_action() {
+    let test_board_2 = Board::try_from(TEST_BOARD_2_STR).unwrap();
+    assert!(test_board_2.has_winning_action(1));
+    assert!(!test_board_2.has_winning_action(0));
+    let mut board = Board::EMPTY;
+    board.init();
+    assert!(!board.has_winning_action(0));
+    assert!(!board.has_winning_action(1));
+
+    // The last row is blocked by a white paper on top of a stack
+    let board =
+        Board::try_from_fen("s-1r-s-rp1/p-1s-wwr-1sp/6/p-5RS/r-2W-W-1/P-1R-2SP1/R-SPS-R-P-1")
+            .unwrap();
+    assert!(!board.has_winning_action(1));
+    // Stacking onto a wise on the last row wins
+    let board = Board::try_from_fen("6/7/6/7/6/2r-4/1w-4").unwrap();
+    assert!(board.has_winning_action(1));
+}
+
 #[test]
 fn test_is_action_legal() {
     let test_board_2 = Board::try_from(TEST_BOARD_2_STR).unwrap();
@@ -115,3 +142,51 @@ fn test_get_winning_player() {
         assert_eq!(input.get_winner(), output);
     }
 }
+
+#[test]
+fn test_validate() {
+    let mut board = Board::EMPTY;
+    board.init();
+    assert!(board.validate().is_ok());
+    assert!(Board::try_from(TEST_BOARD_2_STR)
+        .unwrap()
+        .validate()
+        .is_ok());
+
+    let invalid_boards = [
+        // Black wise on a white rock
+        ("6/7/6/3Rw3/6/7/6", InvalidPositionKind::MixedStack(22)),
+        ("6/7/6/3RW3/6/7/6", InvalidPositionKind::WiseOnNonWise(22)),
+        (
+            "6/7/6/W-W-W-4/6/7/6",
+            InvalidPositionKind::TooManyPieces {
+                piece: WHITE_WISE,
+                count: 3,
+                max: 2,
+            },
+        ),
+        (
+            "6/7/6/PPPPP-4/6/7/6",
+            InvalidPositionKind::TooManyPieces {
+                piece: WHITE_PAPER,
+                count: 5,
+                max: 4,
+            },
+        ),
+        ("S-5/7/6/7/6/7/6", InvalidPositionKind::AlreadyWon(0)),
+        ("6/7/6/7/6/7/s-5", InvalidPositionKind::AlreadyWon(1)),
+    ];
+    for (fen, expected) in invalid_boards {
+        assert_eq!(
+            Board::try_from_fen(fen).unwrap().validate(),
+            Err(expected),
+            "{fen}"
+        );
+    }
+
+    // Unusual positions can be set when they are allowed
+    let mut game = Game::new();
+    assert!(game.set_string_state("6/7/6/3RW3/6/7/6 w 0 1").is_err());
+    game.options.allow_unusual_positions = true;
+    assert!(game.set_string_state("6/7/6/3RW3/6/7/6 w 0 1").is_ok());
+}
@@ -0,0 +1,148 @@
+use pijersi_rs::{
+    bitboard::Board,
+    logic::{
+        actions::{Action, ActionTrait},
+        index::CellIndexTrait,
+        symmetry::Symmetry,
+        translate::{string_to_action, string_to_index},
+        Player,
+    },
+    search::eval::{evaluate_position, evaluate_terms, EvalWeights},
+};
+use proptest::prelude::*;
+
+/// Plays random actions from the starting position (each choice picks an action among the available ones) and returns the position reached.
+fn random_position(choices: &[usize]) -> (Board, Player) {
+    let mut board = Board::EMPTY;
+    board.init();
+    let mut player = 0;
+    for &choice in choices {
+        let actions = board.available_player_actions(player);
+        if actions.is_empty() || board.get_winner().is_some() {
+            break;
+        }
+        board.play_action(actions[choice % actions.len()]);
+        player = 1 - player;
+    }
+    (board, player)
+}
+
+/// Returns the sorted available actions of a player.
+fn sorted_actions(board: &Board, player: Player) -> Vec<Action> {
+    let actions = board.available_player_actions(player);
+    let mut actions: Vec<Action> = actions[0..actions.len()].to_vec();
+    actions.sort_unstable();
+    actions
+}
+
+#[test]
+fn test_index_symmetries() {
+    for (cell, mirrored, rotated) in [
+        ("a1", "a6", "g6"),
+        ("b1", "b7", "f7"),
+        ("d4", "d4", "d4"),
+        ("g2", "g5", "a5"),
+    ] {
+        let index = string_to_index(cell).unwrap();
+        assert_eq!(index.mirror(), string_to_index(mirrored).unwrap());
+        assert_eq!(index.rotate(), string_to_index(rotated).unwrap());
+    }
+}
+
+#[test]
+fn test_board_symmetries() {
+    let mut board = Board::EMPTY;
+    board.init();
+    assert_eq!(board.flip_colours(), board);
+    let board = Board::try_from_fen("6/7/6/7/6/RP6/PS5").unwrap();
+    assert_eq!(board.mirror().to_fen(), "6/7/6/7/6/6RP/5PS");
+    assert_eq!(board.flip_colours().to_fen(), "5ps/6rp/6/7/6/7/6");
+
+    let action = string_to_action(&board, "b1c1").unwrap();
+    assert_eq!(
+        action.mirror(),
+        string_to_action(&board.mirror(), "b7c6").unwrap()
+    );
+    assert_eq!(
+        action.flip_colours(),
+        string_to_action(&board.flip_colours(), "f7e6").unwrap()
+    );
+}
+
+#[test]
+fn test_canonical() {
+    let mut board = Board::EMPTY;
+    board.init();
+    // The starting position is invariant when the colours are flipped
+    let (canonical, symmetry) = board.canonical(0);
+    assert!(!symmetry.flips_colours());
+    assert!(canonical == board || canonical == board.mirror());
+    let (other_canonical, other_symmetry) = board.canonical(1);
+    assert!(other_symmetry.flips_colours());
+    assert_eq!(other_canonical, canonical);
+}
+
+proptest! {
+    #[test]
+    fn test_symmetries_are_involutions(choices in prop::collection::vec(any::<usize>(), 0..40)) {
+        let (board, _player) = random_position(&choices);
+        prop_assert_eq!(board.mirror().mirror(), board);
+        prop_assert_eq!(board.flip_colours().flip_colours(), board);
+        prop_assert_eq!(board.mirror().flip_colours(), board.flip_colours().mirror());
+    }
+
+    #[test]
+    fn test_movegen_commutes(choices in prop::collection::vec(any::<usize>(), 0..40)) {
+        let (board, player) = random_position(&choices);
+        for symmetry in Symmetry::ALL {
+            let new_board = symmetry.apply_board(&board);
+            let new_player = symmetry.apply_player(player);
+            let mut expected: Vec<Action> = sorted_actions(&board, player)
+                .into_iter()
+                .map(|action| symmetry.apply_action(action))
+                .collect();
+            expected.sort_unstable();
+            prop_assert_eq!(sorted_actions(&new_board, new_player), expected);
+
+            for action in sorted_actions(&board, player) {
+                let new_action = symmetry.apply_action(action);
+                prop_assert_eq!(
+                    new_board.is_action_win(new_action, new_player),
+                    board.is_action_win(action, player)
+                );
+                let mut played = board;
+                played.play_action(action);
+                let mut new_played = new_board;
+                new_played.play_action(new_action);
+                prop_assert_eq!(symmetry.apply_board(&played), new_played);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eval_commutes(choices in prop::collection::vec(any::<usize>(), 0..40)) {
+        let (board, _player) = random_position(&choices);
+        let weights = EvalWeights {
+            mobility: 1,
+            threats: 1,
+            unprotected: 1,
+            advancement: 1,
+            wise_blocking: 1,
+        };
+        prop_assert_eq!(evaluate_position(&board.mirror()), evaluate_position(&board));
+        prop_assert_eq!(evaluate_position(&board.flip_colours()), -evaluate_position(&board));
+        prop_assert_eq!(evaluate_terms(&board.mirror(), &weights), evaluate_terms(&board, &weights));
+        prop_assert_eq!(evaluate_terms(&board.flip_colours(), &weights), -evaluate_terms(&board, &weights));
+    }
+
+    #[test]
+    fn test_canonical_is_invariant(choices in prop::collection::vec(any::<usize>(), 0..40)) {
+        let (board, player) = random_position(&choices);
+        let (canonical, symmetry) = board.canonical(player);
+        prop_assert_eq!(symmetry.apply_board(&board), canonical);
+        for other in Symmetry::ALL {
+            let (other_canonical, _) = other.apply_board(&board).canonical(other.apply_player(player));
+            prop_assert_eq!(other_canonical, canonical);
+        }
+    }
+}
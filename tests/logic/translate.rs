@@ -1,10 +1,12 @@
 use pijersi_rs::{
     bitboard::{Bitboard, Board},
+    errors::{InvalidPositionKind, ParseError, ParseErrorKind},
     logic::{
         actions::ActionTrait,
         translate::{
-            action_to_string, char_to_piece, coords_to_index, index_to_coords, index_to_string,
-            piece_to_char, player_to_string, string_to_action, string_to_player,
+            action_to_string, cells_to_pretty_string, char_to_piece, coords_to_index,
+            index_to_coords, index_to_string, piece_to_char, player_to_string, string_to_action,
+            string_to_player,
         },
     },
     piece::{
@@ -296,6 +298,47 @@ fn test_board_try_from_fen() {
             .is_err()
     );
     assert!(Board::try_from_fen("").is_err());
+
+    let invalid_fens = [
+        (
+            "6/7/6/8/6/7/6",
+            InvalidPositionKind::WrongCellNumber {
+                line: 3,
+                count: 8,
+                expected: 7,
+            },
+        ),
+        (
+            "6/7/5/7/6/7/6",
+            InvalidPositionKind::WrongCellNumber {
+                line: 2,
+                count: 5,
+                expected: 6,
+            },
+        ),
+        (
+            "6/7/6/x-6/6/7/6",
+            InvalidPositionKind::InvalidCharacter('x'),
+        ),
+        (
+            "6/7/6/-S6/6/7/6",
+            InvalidPositionKind::InvalidCharacter('-'),
+        ),
+        ("6/7/6/6S/6/7/6", InvalidPositionKind::InvalidCharacter('S')),
+        (
+            "6/7/6/0S-6/6/7/6",
+            InvalidPositionKind::InvalidCharacter('0'),
+        ),
+    ];
+    for (fen, expected) in invalid_fens {
+        match Board::try_from_fen(fen) {
+            Err(ParseError {
+                kind: ParseErrorKind::InvalidPosition(kind),
+                ..
+            }) => assert_eq!(kind, expected, "{fen}"),
+            result => panic!("{fen}: unexpected result {result:?}"),
+        }
+    }
 }
 
 #[test]
@@ -311,6 +354,17 @@ fn test_board_to_pretty_string() {
     assert_eq!(TEST_BOARD.to_pretty_string(), " s- p- r- s- .  r- \np- r- s- ww .  s- p- \n .  .  .  .  .  .  \n.  .  .  .  .  pr .  \n .  .  .  .  .  .  \nP- S- R- WW S- R- SP \n R- P- S- R- P- .  ");
 }
 
+#[test]
+fn test_cells_to_pretty_string() {
+    // The cells are right-aligned and the rows with 6 cells are shifted by half a cell
+    let pretty_string = cells_to_pretty_string(3, |index| index.to_string());
+    let lines: Vec<&str> = pretty_string.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "    0   1   2   3   4   5 ");
+    assert_eq!(lines[1], "  6   7   8   9  10  11  12 ");
+    assert_eq!(lines[6], "   39  40  41  42  43  44 ");
+}
+
 #[test]
 fn test_string_to_player() {
     assert_eq!(string_to_player("w").unwrap(), 0);
@@ -0,0 +1,302 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::RwLock;
+
+use pijersi_rs::{
+    bitboard::Board,
+    game::Game,
+    hash::search::SearchTable,
+    logic::rules::is_action_legal,
+    search::{
+        alphabeta::{
+            score_to_string, search_iterative, search_lazy_smp, search_root, SearchContext,
+            SearchLine, BASE_ALPHA, BASE_BETA,
+        },
+        eval::{win_score, EvalWeights},
+    },
+};
+
+use crate::TEST_BOARD_STR;
+
+#[test]
+fn test_search_root_window() {
+    let board = Board::try_from(TEST_BOARD_STR).unwrap();
+    let context = SearchContext::new(None, None, &EvalWeights::NONE);
+    let (_action, score, _scores) =
+        search_root(&board, 0, 3, &None, (BASE_ALPHA, BASE_BETA), &context).unwrap();
+
+    // Fail-low: the score is an upper bound
+    let (_action, score_low, _scores) =
+        search_root(&board, 0, 3, &None, (score + 1, score + 100), &context).unwrap();
+    assert!(score_low <= score + 1);
+
+    // Fail-high: the score is a lower bound
+    let (_action, score_high, _scores) =
+        search_root(&board, 0, 3, &None, (score - 100, score - 1), &context).unwrap();
+    assert!(score_high >= score - 1);
+
+    // Exact score inside the window
+    let (_action, score_exact, _scores) =
+        search_root(&board, 0, 3, &None, (score - 100, score + 100), &context).unwrap();
+    assert_eq!(score_exact, score);
+}
+
+#[test]
+fn test_search_iterative_aspiration() {
+    let board = Board::try_from(TEST_BOARD_STR).unwrap();
+    let mut context = SearchContext::new(None, None, &EvalWeights::NONE);
+    // The pruning depends on the search window
+    context.use_lmr = false;
+    context.use_null_move = false;
+    let (_action, score, _scores) =
+        search_root(&board, 0, 4, &None, (BASE_ALPHA, BASE_BETA), &context).unwrap();
+    let (_action, score_iterative) = search_iterative(&board, 0, 4, &context).unwrap();
+    assert_eq!(score_iterative, score);
+}
+
+#[test]
+fn test_search_line() {
+    let line = SearchLine::default();
+    assert_eq!(line.ply, 0);
+    assert!(!line.null_move);
+    let line = line.next(1, false).next_null_move();
+    assert_eq!(line.ply, 2);
+    assert!(line.null_move);
+    let line = line.next(2, false);
+    assert_eq!(line.ply, 3);
+    assert!(!line.null_move);
+    assert_eq!(line.extensions, 0);
+    let line = line.extend();
+    assert_eq!(line.ply, 3);
+    assert_eq!(line.extensions, 1);
+    assert_eq!(line.next(3, false).extensions, 1);
+
+    // Half moves and repetitions
+    assert_eq!(line.half_moves, 3);
+    assert!(line.is_repetition(2));
+    assert!(!line.is_repetition(1));
+    let line = line.next(3, true);
+    assert_eq!(line.half_moves, 0);
+    assert!(!line.is_repetition(2));
+    assert!(!line.is_repetition(3));
+
+    let line = SearchLine::root(5, &[1, 2, 3]);
+    assert_eq!(line.ply, 0);
+    assert_eq!(line.half_moves, 5);
+    assert!(line.is_repetition(1));
+    assert_eq!(line.draw_score(10), -10);
+    assert_eq!(line.next(4, false).draw_score(10), 10);
+}
+
+#[test]
+fn test_search_pruning() {
+    // Black wins immediately with c6b6a5 or c6b6a6
+    let board =
+        Board::try_from_fen("s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2").unwrap();
+    let board_start = Board::try_from(TEST_BOARD_STR).unwrap();
+    let mut context = SearchContext::new(None, None, &EvalWeights::NONE);
+    for (use_lmr, use_null_move) in [(false, false), (true, false), (false, true), (true, true)] {
+        context.use_lmr = use_lmr;
+        context.use_null_move = use_null_move;
+        let (action, _score) = search_iterative(&board, 1, 5, &context).unwrap();
+        assert!(board.is_action_win(action, 1));
+        assert!(search_iterative(&board_start, 0, 4, &context).is_some());
+    }
+}
+
+#[test]
+fn test_search_mate_distance() {
+    let mut context = SearchContext::new(None, None, &EvalWeights::NONE);
+    context.use_lmr = false;
+    context.use_null_move = false;
+
+    // Black wins immediately with c6b6a5 or c6b6a6
+    let board =
+        Board::try_from_fen("s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2").unwrap();
+    let (_action, score) = search_iterative(&board, 1, 4, &context).unwrap();
+    assert_eq!(score, win_score(1));
+    assert_eq!(score_to_string(score), "mate 1");
+
+    // White wins in 2 actions
+    let board =
+        Board::try_from_fen("1ss1r-2/2w-rps-2/rpw-1rp2/1p-s-1P-1P-/2R-R-P-1/1WW5/R-1S-RS1S-")
+            .unwrap();
+    let (_action, score) = search_iterative(&board, 0, 4, &context).unwrap();
+    assert_eq!(score, win_score(3));
+    assert_eq!(score_to_string(score), "mate 2");
+
+    // Black wins after any white action
+    let board =
+        Board::try_from_fen("3s-p-r-/ps1s-2p-r-/r-2r-2/2ww3RP/1P-p-W-2/3S-s-2/PRS-1WP1S-").unwrap();
+    let (_action, score) = search_iterative(&board, 0, 4, &context).unwrap();
+    assert_eq!(score, -win_score(2));
+    assert_eq!(score_to_string(score), "mate -1");
+
+    assert_eq!(score_to_string(120), "120");
+}
+
+#[test]
+fn test_search_threat_extensions() {
+    // White wins in 2 actions: the first action creates a threat that cannot be stopped
+    let board =
+        Board::try_from_fen("1ss1r-2/2w-rps-2/rpw-1rp2/1p-s-1P-1P-/2R-R-P-1/1WW5/R-1S-RS1S-")
+            .unwrap();
+    let mut context = SearchContext::new(None, None, &EvalWeights::NONE);
+
+    // Black is threatened at the edge of the search, the extension finds the win
+    let (_action, score, _scores) =
+        search_root(&board, 0, 1, &None, (BASE_ALPHA, BASE_BETA), &context).unwrap();
+    assert_eq!(score, win_score(3));
+
+    context.use_threat_extensions = false;
+    let (_action, score, _scores) =
+        search_root(&board, 0, 1, &None, (BASE_ALPHA, BASE_BETA), &context).unwrap();
+    assert!(score < BASE_BETA);
+}
+
+#[test]
+fn test_search_modes() {
+    // Black wins immediately with c6b6a5 or c6b6a6
+    let board =
+        Board::try_from_fen("s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2").unwrap();
+    let transposition_table = RwLock::new(SearchTable::default());
+    let mut context = SearchContext::new(None, Some(&transposition_table), &EvalWeights::NONE);
+
+    // Sequential search
+    context.parallel = false;
+    let (action, score) = search_iterative(&board, 1, 3, &context).unwrap();
+    assert!(board.is_action_win(action, 1));
+    assert_eq!(score, win_score(1));
+
+    // Lazy SMP
+    context.parallel = true;
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let (action, score) = thread_pool
+        .install(|| search_lazy_smp(&board, 1, 3, &context))
+        .unwrap();
+    assert!(board.is_action_win(action, 1));
+    assert_eq!(score, win_score(1));
+
+    // The search does not start if the stop flag is set
+    let stop = AtomicBool::new(true);
+    context.stop = Some(&stop);
+    assert!(search_iterative(&board, 1, 3, &context).is_none());
+}
+
+#[test]
+fn test_lazy_smp_threads() {
+    let positions = [
+        // White wins in 2 actions
+        (
+            "1ss1r-2/2w-rps-2/rpw-1rp2/1p-s-1P-1P-/2R-R-P-1/1WW5/R-1S-RS1S-",
+            0,
+        ),
+        // Black wins after any white action
+        (
+            "3s-p-r-/ps1s-2p-r-/r-2r-2/2ww3RP/1P-p-W-2/3S-s-2/PRS-1WP1S-",
+            0,
+        ),
+    ];
+    for (fen, player) in positions {
+        let board = Board::try_from_fen(fen).unwrap();
+        // The main thread returns a legal action with the score of the single-thread search at the same depth
+        let results: Vec<_> = [1, 4]
+            .into_iter()
+            .map(|threads| {
+                let transposition_table = RwLock::new(SearchTable::default());
+                let context =
+                    SearchContext::new(None, Some(&transposition_table), &EvalWeights::NONE);
+                let thread_pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap();
+                thread_pool
+                    .install(|| search_lazy_smp(&board, player, 4, &context))
+                    .unwrap()
+            })
+            .collect();
+        for (action, _score) in &results {
+            assert!(is_action_legal(&board, player, *action), "{fen}");
+        }
+        assert_eq!(results[0].1, results[1].1, "{fen}");
+    }
+}
+
+#[test]
+fn test_lazy_smp_consecutive() {
+    let mut game = Game::new();
+    game.init();
+    game.play_from_string("a5b5d4").unwrap();
+    let board = game.board;
+    let transposition_table = RwLock::new(SearchTable::default());
+    let mut context = SearchContext::new(None, Some(&transposition_table), &EvalWeights::NONE);
+    context.parallel = false;
+    let expected = search_iterative(&board, 1, 3, &context);
+    assert!(expected.is_some());
+
+    // The helper threads stopped by the end of a search do not leave wrong scores in the shared table
+    let transposition_table = RwLock::new(SearchTable::default());
+    let context = SearchContext::new(None, Some(&transposition_table), &EvalWeights::NONE);
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        let result = thread_pool.install(|| search_lazy_smp(&board, 1, 3, &context));
+        assert_eq!(
+            result.map(|(_action, score)| score),
+            expected.map(|(_action, score)| score)
+        );
+    }
+}
+
+#[test]
+fn test_search_deterministic() {
+    let mut game = Game::new();
+    game.init();
+    game.play_from_string("a5b5d4").unwrap();
+    game.options.deterministic = true;
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+
+    // The same position, depth and table state yield the same result
+    let results: Vec<_> = (0..3)
+        .map(|_| {
+            let transposition_table = RwLock::new(SearchTable::default());
+            thread_pool.install(|| game.search_to_depth(4, None, Some(&transposition_table)))
+        })
+        .collect();
+    assert!(results[0].is_some());
+    assert!(results.iter().all(|&result| result == results[0]));
+
+    // Searching again with the filled table also yields the same result
+    let transposition_table = RwLock::new(SearchTable::default());
+    let first = game.search_to_depth(3, None, Some(&transposition_table));
+    let second = game.search_to_depth(3, None, Some(&transposition_table));
+    let transposition_table_2 = RwLock::new(SearchTable::default());
+    assert_eq!(
+        first,
+        game.search_to_depth(3, None, Some(&transposition_table_2))
+    );
+    assert_eq!(
+        second,
+        game.search_to_depth(3, None, Some(&transposition_table_2))
+    );
+}
+
+#[test]
+fn test_search_contempt() {
+    let mut game = Game::new();
+    // No capture is possible, every action reaches the half move limit
+    game.set_string_state("s-p-r-s-p-r-/p-r-s-wwr-s-p-/6/7/6/P-S-R-WWS-R-P-/R-P-S-R-P-S- w 19 10")
+        .unwrap();
+    for contempt in [-300, 0, 300] {
+        game.options.contempt = contempt;
+        let (_action, score) = game.search_to_depth(2, None, None).unwrap();
+        assert_eq!(score, -contempt);
+    }
+}
@@ -0,0 +1,120 @@
+use std::env::temp_dir;
+use std::sync::RwLock;
+
+use pijersi_rs::{
+    datagen::{
+        generate, play_game, read_binary, write_binary, write_text, DatagenOptions,
+        TrainingPosition,
+    },
+    engine::Engine,
+    game::{Game, GameOptions},
+    hash::search::SearchTable,
+    logic::rules::is_action_legal,
+    search::eval::EvalWeights,
+};
+
+/// Checks that the positions are consecutive positions of a game with its result.
+fn check_game(positions: &[TrainingPosition]) {
+    let result = positions[0].result;
+    assert!([-1, 0, 1].contains(&result));
+    for (position, next_position) in positions.iter().zip(&positions[1..]) {
+        assert_eq!(position.result, result);
+        assert_eq!(next_position.player, 1 - position.player);
+        let mut game = Game::new();
+        game.set_string_state(&position.to_psn()).unwrap();
+        assert!(is_action_legal(
+            &game.board,
+            game.current_player,
+            position.action
+        ));
+        game.play(position.action).unwrap();
+        assert_eq!(game.get_string_state(), next_position.to_psn());
+    }
+}
+
+#[test]
+fn test_play_game() {
+    let options = DatagenOptions {
+        depth: 1,
+        random_plies: 4,
+        ..DatagenOptions::default()
+    };
+    let game_options = GameOptions::new();
+    let positions = play_game(&options, 0, &game_options, None);
+    assert!(!positions.is_empty());
+    assert_eq!(positions, play_game(&options, 0, &game_options, None));
+    check_game(&positions);
+}
+
+#[test]
+fn test_play_game_options() {
+    // The searches are limited by the number of nodes, the deterministic searches are reproducible
+    let options = DatagenOptions {
+        depth: 64,
+        nodes: Some(200),
+        random_plies: 4,
+        ..DatagenOptions::default()
+    };
+    let mut game_options = GameOptions::new();
+    game_options.deterministic = true;
+    let positions = play_game(&options, 0, &game_options, None);
+    assert!(!positions.is_empty());
+    assert_eq!(positions, play_game(&options, 0, &game_options, None));
+    check_game(&positions);
+
+    // The searches use the evaluation weights and the transposition table
+    game_options.eval_weights = EvalWeights {
+        mobility: 4,
+        ..EvalWeights::NONE
+    };
+    let transposition_table = RwLock::new(SearchTable::default());
+    let weighted_positions = play_game(&options, 0, &game_options, Some(&transposition_table));
+    check_game(&weighted_positions);
+    assert_ne!(weighted_positions[0].score, positions[0].score);
+
+    // A game that cannot be finished is discarded instead of being labelled as a draw
+    let options = DatagenOptions {
+        nodes: Some(0),
+        ..options
+    };
+    assert!(play_game(&options, 0, &game_options, None).is_empty());
+}
+
+#[test]
+fn test_engine_generate_data() {
+    let mut engine = Engine::new();
+    engine.game.options.deterministic = true;
+    let options = DatagenOptions {
+        games: 2,
+        depth: 1,
+        random_plies: 4,
+        seed: 1,
+        ..DatagenOptions::default()
+    };
+    assert_eq!(
+        engine.generate_data(&options),
+        generate(&options, &engine.game.options, None)
+    );
+}
+
+#[test]
+fn test_write_read() {
+    let options = DatagenOptions {
+        games: 2,
+        depth: 1,
+        random_plies: 4,
+        seed: 1,
+        ..DatagenOptions::default()
+    };
+    let positions = generate(&options, &GameOptions::new(), None);
+    let path_binary = temp_dir().join("pijersi_test_datagen.bin");
+    let path_text = temp_dir().join("pijersi_test_datagen.txt");
+    write_binary(&path_binary, &positions).unwrap();
+    write_text(&path_text, &positions).unwrap();
+
+    assert_eq!(read_binary(&path_binary).unwrap(), positions);
+    let text = std::fs::read_to_string(&path_text).unwrap();
+    assert_eq!(text.lines().count(), positions.len());
+    assert_eq!(text.lines().next().unwrap(), positions[0].to_text());
+    assert_eq!(text.lines().next().unwrap().split(';').count(), 4);
+}
@@ -0,0 +1,109 @@
+use std::sync::mpsc::channel;
+
+use pijersi_rs::{
+    engine::Engine,
+    game::SearchLimits,
+    hash::position::HashTrait,
+    logic::rules::is_action_legal,
+    search::{eval::EvalTerm, info::SearchInfo},
+};
+
+#[test]
+fn test_engine_search_info() {
+    let mut engine = Engine::new();
+    engine.set_threads(2).unwrap();
+    engine.game.options.deterministic = true;
+    engine.game.play_from_string("a5b5d4").unwrap();
+
+    let (sender, receiver) = channel();
+    let result = engine.search(
+        &SearchLimits::depth(3),
+        Some(&|info| {
+            sender.send(info).unwrap();
+        }),
+    );
+    drop(sender);
+    let result = result.unwrap();
+    let (action, score) = (result.action.action(), result.score);
+    assert!(is_action_legal(
+        &engine.game.board,
+        engine.game.current_player,
+        action
+    ));
+
+    let events: Vec<SearchInfo> = receiver.iter().collect();
+    let depths: Vec<u64> = events
+        .iter()
+        .filter_map(|info| match info {
+            SearchInfo::Iteration {
+                depth, bound: None, ..
+            } => Some(*depth),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(depths, vec![1, 2, 3]);
+    assert!(matches!(
+        events.last(),
+        Some(&SearchInfo::Iteration { action: last_action, score: last_score, .. })
+            if last_action == action && last_score == score
+    ));
+    assert!(!events
+        .iter()
+        .any(|info| matches!(info, SearchInfo::Stats(_))));
+
+    // The statistics are reported in debug mode
+    engine.game.options.debug = true;
+    let (sender, receiver) = channel();
+    assert_eq!(
+        engine.search(
+            &SearchLimits::depth(3),
+            Some(&|info| {
+                sender.send(info).unwrap();
+            })
+        ),
+        Some(result)
+    );
+    drop(sender);
+    assert!(matches!(receiver.iter().last(), Some(SearchInfo::Stats(stats)) if stats.nodes > 0));
+
+    // The search is silent without a callback
+    assert_eq!(engine.search(&SearchLimits::depth(3), None), Some(result));
+}
+
+#[test]
+fn test_engine_eval_weight_clears_table() {
+    let mut engine = Engine::new();
+    engine.load();
+    engine.game.options.deterministic = true;
+    engine.search(&SearchLimits::depth(3), None).unwrap();
+
+    // Counts the positions after the first action that are in the transposition table
+    let count_entries = |engine: &Engine| {
+        let board = engine.game.board;
+        let player = engine.game.current_player;
+        let transposition_table = engine.transposition_table().unwrap().read().unwrap();
+        board
+            .available_player_actions(player)
+            .into_iter()
+            .filter(|&action| {
+                let mut new_board = board;
+                new_board.play_action(action);
+                transposition_table
+                    .read((&new_board, 1 - player).hash())
+                    .is_some()
+            })
+            .count()
+    };
+    assert!(count_entries(&engine) > 0);
+
+    // The table is kept if the weight does not change, it is emptied otherwise
+    let weight = engine.game.options.eval_weights.get(EvalTerm::Mobility);
+    engine.set_eval_weight(EvalTerm::Mobility, weight);
+    assert!(count_entries(&engine) > 0);
+    engine.set_eval_weight(EvalTerm::Mobility, weight + 1);
+    assert_eq!(
+        engine.game.options.eval_weights.get(EvalTerm::Mobility),
+        weight + 1
+    );
+    assert_eq!(count_entries(&engine), 0);
+}
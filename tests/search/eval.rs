@@ -0,0 +1,119 @@
+use pijersi_rs::{
+    bitboard::Board,
+    search::{
+        alphabeta::BASE_BETA,
+        eval::{
+            evaluate_position, evaluate_position_for_player, evaluate_term, evaluate_terms,
+            mate_distance, win_score, EvalTerm, EvalTrace, EvalWeights,
+        },
+    },
+};
+
+use crate::TEST_BOARD_STR;
+
+#[test]
+fn test_evaluate_terms_disabled() {
+    let test_board = Board::try_from(TEST_BOARD_STR).unwrap();
+    assert_eq!(evaluate_terms(&test_board, &EvalWeights::NONE), 0);
+    assert_eq!(
+        evaluate_position_for_player(&test_board, 0, &EvalWeights::NONE),
+        evaluate_position(&test_board)
+    );
+    assert_eq!(
+        evaluate_position_for_player(&test_board, 1, &EvalWeights::NONE),
+        -evaluate_position(&test_board)
+    );
+}
+
+#[test]
+fn test_evaluate_terms_startpos() {
+    let mut board = Board::EMPTY;
+    board.init();
+    let mut weights = EvalWeights::NONE;
+    for term in EvalTerm::ALL {
+        weights.set(term, 1);
+        assert_eq!(
+            evaluate_term(&board, term, 0),
+            evaluate_term(&board, term, 1)
+        );
+    }
+    assert_eq!(evaluate_terms(&board, &weights), 0);
+    assert_eq!(evaluate_term(&board, EvalTerm::Mobility, 0), 186);
+    assert_eq!(evaluate_term(&board, EvalTerm::Threats, 0), 0);
+    assert_eq!(evaluate_term(&board, EvalTerm::Advancement, 0), 1);
+}
+
+#[test]
+fn test_evaluate_terms_weighted() {
+    let test_board = Board::try_from(TEST_BOARD_STR).unwrap();
+    for term in EvalTerm::ALL {
+        let mut weights = EvalWeights::NONE;
+        weights.set(term, 3);
+        assert_eq!(weights.get(term), 3);
+        assert!(!weights.is_pst_only());
+        assert_eq!(
+            evaluate_terms(&test_board, &weights),
+            3 * (evaluate_term(&test_board, term, 0) - evaluate_term(&test_board, term, 1))
+        );
+    }
+}
+
+#[test]
+fn test_evaluate_term_threats() {
+    // The white stack in b7 can capture the lone black stack in d6 (2-range move)
+    let test_board = Board::try_from(TEST_BOARD_STR).unwrap();
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Threats, 0), 1);
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Threats, 1), 0);
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Unprotected, 0), 0);
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Unprotected, 1), -1);
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Advancement, 0), 1);
+    assert_eq!(evaluate_term(&test_board, EvalTerm::Advancement, 1), 3);
+}
+
+#[test]
+fn test_eval_trace() {
+    let test_board = Board::try_from(TEST_BOARD_STR).unwrap();
+    let mut weights = EvalWeights::NONE;
+    weights.set(EvalTerm::Mobility, 2);
+    weights.set(EvalTerm::Advancement, 5);
+    let trace = EvalTrace::new(&test_board, &weights);
+    assert_eq!(trace.pst(0) + trace.pst(1), evaluate_position(&test_board));
+    assert_eq!(
+        trace.total(),
+        evaluate_position_for_player(&test_board, 0, &weights)
+    );
+    assert_eq!(
+        trace.cells.iter().sum::<i16>(),
+        evaluate_position(&test_board)
+    );
+    let trace_string = trace.to_pretty_string();
+    assert_eq!(trace_string.lines().count(), 32);
+    assert_eq!(
+        trace_string.lines().next(),
+        Some("evaluator piece-square tables and terms")
+    );
+
+    // The clamp of the terms is shown on its own line
+    weights.set(EvalTerm::Advancement, i16::MAX);
+    let trace = EvalTrace::new(&test_board, &weights);
+    let clamped = evaluate_terms(&test_board, &weights);
+    assert!((clamped as i32) < trace.terms_sum().abs());
+    assert_eq!(trace.total(), trace.pst(0) + trace.pst(1) + clamped);
+    assert!(trace.to_pretty_string().contains(&format!(
+        "terms sum {} clamped to [-2048, 2048] {clamped}\n",
+        trace.terms_sum()
+    )));
+}
+
+#[test]
+fn test_mate_distance() {
+    assert_eq!(mate_distance(win_score(1)), Some(1));
+    assert_eq!(mate_distance(win_score(2)), Some(1));
+    assert_eq!(mate_distance(win_score(3)), Some(2));
+    assert_eq!(mate_distance(-win_score(2)), Some(-1));
+    assert_eq!(mate_distance(-win_score(4)), Some(-2));
+    assert_eq!(mate_distance(0), None);
+    assert_eq!(mate_distance(BASE_BETA), None);
+    assert!(win_score(1) > win_score(3));
+    assert!(win_score(3) > BASE_BETA);
+}
@@ -0,0 +1,21 @@
+mod alphabeta;
+mod datagen;
+mod engine;
+mod eval;
+#[cfg(feature = "nnue")]
+mod nnue;
+mod ordering;
+mod skill;
+mod stats;
+mod suite;
+
+/// Cells state for testing
+///  s- p- r- s- .  r-
+/// p- r- s- ww .  s- p-
+///  .  .  .  .  .  .
+/// .  .  .  .  .  pr .
+///  .  .  .  .  .  .
+/// P- S- R- WW S- R- SP
+///  R- P- S- R- P- .
+const TEST_BOARD_STR: &str =
+    "s-p-r-s-..r-p-r-s-ww..s-p-......................pr..............P-S-R-WWS-R-SPR-P-S-R-P-..";
@@ -0,0 +1,63 @@
+use pijersi_rs::{
+    bitboard::Board,
+    search::nnue::{Accumulator, Network, N_FEATURES, N_HIDDEN},
+};
+
+/// Creates a network with arbitrary but deterministic weights.
+fn test_network() -> Network {
+    let feature_weights = (0..N_FEATURES)
+        .map(|feature| {
+            let mut layer = [0i16; N_HIDDEN];
+            for (hidden, weight) in layer.iter_mut().enumerate() {
+                *weight = ((feature * 31 + hidden * 17) % 41) as i16 - 20;
+            }
+            layer
+        })
+        .collect();
+    let feature_biases = [64; N_HIDDEN];
+    let mut output_weights = [0i16; N_HIDDEN];
+    for (hidden, weight) in output_weights.iter_mut().enumerate() {
+        *weight = (hidden as i16 % 7) - 3;
+    }
+    Network::from_weights(feature_weights, feature_biases, output_weights, 100)
+}
+
+#[test]
+fn test_network_bytes() {
+    let network = test_network();
+    let bytes = network.to_bytes();
+    let network_copy = Network::from_bytes(&bytes).unwrap();
+    assert_eq!(network_copy.to_bytes(), bytes);
+
+    let mut board = Board::EMPTY;
+    board.init();
+    assert_eq!(
+        Accumulator::new(&network, &board).evaluate(&network),
+        Accumulator::new(&network_copy, &board).evaluate(&network_copy)
+    );
+
+    assert!(Network::from_bytes(b"NNJP").is_err());
+    assert!(Network::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_accumulator_update() {
+    let network = test_network();
+    let mut board = Board::EMPTY;
+    board.init();
+    let mut accumulator = Accumulator::new(&network, &board);
+    let mut current_player = 0;
+    for ply in 0..200 {
+        let available_actions = board.available_player_actions(current_player);
+        if available_actions.is_empty() || board.is_win() {
+            break;
+        }
+        let action = available_actions[(ply * 7919) % available_actions.len()];
+        let mut new_board = board;
+        new_board.play_action(action);
+        accumulator.update(&network, &board, &new_board, action);
+        assert_eq!(accumulator, Accumulator::new(&network, &new_board));
+        board = new_board;
+        current_player = 1 - current_player;
+    }
+}
@@ -0,0 +1,41 @@
+use pijersi_rs::search::ordering::{HistoryTable, KillerTable, SearchHeuristics, MAX_KILLER_PLY};
+
+#[test]
+fn test_killer_table() {
+    let killers = KillerTable::default();
+    assert_eq!(killers.get(3), [0, 0]);
+    killers.store(3, 1975583);
+    assert_eq!(killers.get(3), [1975583, 0]);
+    killers.store(3, 2893087);
+    assert_eq!(killers.get(3), [2893087, 1975583]);
+    // Storing the first killer again does not erase the second one
+    killers.store(3, 2893087);
+    assert_eq!(killers.get(3), [2893087, 1975583]);
+    assert_eq!(killers.get(2), [0, 0]);
+
+    // Plies out of range are ignored
+    killers.store(MAX_KILLER_PLY as u64, 1975583);
+    assert_eq!(killers.get(MAX_KILLER_PLY as u64), [0, 0]);
+}
+
+#[test]
+fn test_history_table() {
+    let history = HistoryTable::default();
+    // a6b7 (single move) and c6b6a6 (stack move)
+    let action_move = 37 | (0xFF << 8) | (31 << 16);
+    let action_stack = 2893087;
+    assert_eq!(history.get(action_move), 0);
+    history.update(action_move, 3);
+    history.update(action_move, 2);
+    history.update(action_stack, 4);
+    assert_eq!(history.get(action_move), 13);
+    assert_eq!(history.get(action_stack), 16);
+}
+
+#[test]
+fn test_search_heuristics() {
+    let heuristics = SearchHeuristics::default();
+    heuristics.update(2893087, 5, 1);
+    assert_eq!(heuristics.killers.get(1), [2893087, 0]);
+    assert_eq!(heuristics.history.get(2893087), 25);
+}
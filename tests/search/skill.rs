@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use pijersi_rs::{
+    bitboard::Board,
+    game::{Game, SearchLimits},
+    logic::rules::is_action_legal,
+    search::{
+        alphabeta::{SearchContext, BASE_ALPHA, BASE_BETA},
+        eval::{win_score, EvalWeights},
+        info::SearchInfo,
+        skill::{
+            choose_skill_action, score_root_actions, skill_depth, skill_margin, MAX_SKILL_LEVEL,
+        },
+    },
+};
+
+use crate::TEST_BOARD_STR;
+
+#[test]
+fn test_skill_limits() {
+    assert_eq!(skill_depth(0), 1);
+    assert_eq!(skill_depth(MAX_SKILL_LEVEL - 1), 5);
+    assert_eq!(skill_depth(MAX_SKILL_LEVEL), u64::MAX);
+    assert!(skill_margin(0) > skill_margin(10));
+    assert_eq!(skill_margin(MAX_SKILL_LEVEL), 0);
+}
+
+#[test]
+fn test_choose_skill_action() {
+    let scored_actions = [(1, 100), (2, -win_score(2)), (3, 90), (4, -5000), (5, 150)];
+    let mut rng = StdRng::seed_from_u64(0);
+
+    // Only the actions close to the best score are chosen, losing actions are never chosen
+    let mut chosen = [false; 6];
+    for _ in 0..200 {
+        let (action, _score) = choose_skill_action(&scored_actions, 0, &mut rng).unwrap();
+        chosen[action as usize] = true;
+    }
+    assert_eq!(chosen, [false, true, false, true, false, true]);
+
+    // The best action is always chosen at the maximum level
+    for _ in 0..20 {
+        assert_eq!(
+            choose_skill_action(&scored_actions, MAX_SKILL_LEVEL, &mut rng),
+            Some((5, 150))
+        );
+    }
+
+    // Wins are always played
+    let scored_actions = [(1, 100), (2, win_score(3)), (3, 90)];
+    for _ in 0..20 {
+        assert_eq!(
+            choose_skill_action(&scored_actions, 0, &mut rng),
+            Some((2, win_score(3)))
+        );
+    }
+
+    assert_eq!(choose_skill_action(&[], 0, &mut rng), None);
+}
+
+#[test]
+fn test_score_root_actions() {
+    let board = Board::try_from(TEST_BOARD_STR).unwrap();
+    let context = SearchContext::new(None, None, &EvalWeights::NONE);
+    let scored_actions = score_root_actions(&board, 0, 2, (BASE_ALPHA, BASE_BETA), &context);
+    let available_actions = board.available_player_actions(0);
+    assert_eq!(scored_actions.len(), available_actions.len());
+    for (index, &(action, _score)) in scored_actions.iter().enumerate() {
+        assert_eq!(action, available_actions[index]);
+    }
+}
+
+#[test]
+fn test_search_skill_level() {
+    let mut game = Game::new();
+    game.options.skill_level = 0;
+
+    // Black wins immediately with c6b6a5 or c6b6a6, the win is always played
+    game.set_string_state("s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2 b 0 1")
+        .unwrap();
+    for _ in 0..10 {
+        let (action, score) = game.search_to_depth(4, None, None).unwrap();
+        assert!(game.board.is_action_win(action, 1));
+        assert_eq!(score, win_score(1));
+    }
+
+    // The chosen action is legal and deterministic searches choose the same action
+    game.init();
+    game.options.deterministic = true;
+    let result = game.search_to_depth(4, None, None);
+    let (action, _score) = result.unwrap();
+    assert!(is_action_legal(&game.board, game.current_player, action));
+    assert_eq!(game.search_to_depth(4, None, None), result);
+}
+
+#[test]
+fn test_search_skill_limits() {
+    let mut game = Game::new();
+    game.init();
+    game.options.skill_level = 4;
+    game.options.deterministic = true;
+    let depth = skill_depth(game.options.skill_level);
+
+    // The skill search runs at the depth completed by the main search, with or without a time limit
+    for movetime in [None, Some(60_000)] {
+        let limits = SearchLimits {
+            depth: Some(depth),
+            movetime,
+            nodes: None,
+        };
+        let skill_infos = Mutex::new(vec![]);
+        let callback = |info: SearchInfo| {
+            if let SearchInfo::Skill { depth, action, .. } = info {
+                skill_infos.lock().unwrap().push((depth, action));
+            }
+        };
+        let (action, _score) = game.search(&limits, None, None, Some(&callback)).unwrap();
+        assert!(is_action_legal(&game.board, game.current_player, action));
+        assert_eq!(skill_infos.into_inner().unwrap(), [(depth, action)]);
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::RwLock;
+
+use pijersi_rs::{
+    bitboard::Board,
+    hash::search::SearchTable,
+    search::{
+        alphabeta::{search_iterative, SearchContext},
+        eval::EvalWeights,
+        stats::SearchStats,
+    },
+};
+
+use crate::TEST_BOARD_STR;
+
+#[test]
+fn test_search_stats() {
+    let board = Board::try_from(TEST_BOARD_STR).unwrap();
+    let transposition_table = RwLock::new(SearchTable::default());
+    let mut context = SearchContext::new(None, Some(&transposition_table), &EvalWeights::NONE);
+    context.parallel = false;
+    let result = search_iterative(&board, 0, 4, &context);
+
+    // Collecting the statistics does not change the search
+    transposition_table.write().unwrap().empty();
+    let stats = SearchStats::default();
+    context.stats = Some(&stats);
+    assert_eq!(search_iterative(&board, 0, 4, &context), result);
+
+    assert!(stats.nodes.load(Relaxed) > 0);
+    assert!(stats.qnodes.load(Relaxed) > 0);
+    assert!(stats.tt_probes.load(Relaxed) <= stats.nodes.load(Relaxed));
+    assert!(stats.tt_hits.load(Relaxed) <= stats.tt_probes.load(Relaxed));
+    assert!(stats.tt_cutoffs.load(Relaxed) <= stats.tt_hits.load(Relaxed));
+    assert!(stats.first_move_cutoffs.load(Relaxed) <= stats.beta_cutoffs.load(Relaxed));
+
+    let iterations = stats.iterations();
+    assert_eq!(
+        iterations
+            .iter()
+            .map(|&(depth, _nodes, _ebf)| depth)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert!(iterations[0].2.is_none());
+    assert!(iterations[1..]
+        .iter()
+        .all(|&(_depth, _nodes, ebf)| ebf.is_some()));
+    assert_eq!(
+        iterations
+            .iter()
+            .map(|&(_depth, nodes, _ebf)| nodes)
+            .sum::<u64>(),
+        stats.total_nodes()
+    );
+
+    let summary = stats.summary();
+    assert_eq!(summary.nodes, stats.nodes.load(Relaxed));
+    assert_eq!(summary.iterations, iterations);
+    assert!((0.0..=1.0).contains(&summary.first_move_cutoff_rate()));
+}
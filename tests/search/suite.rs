@@ -0,0 +1,65 @@
+use pijersi_rs::{
+    game::{Game, GameOptions},
+    search::suite::{parse_suite, run_suite, SuiteEntry, SuiteLimit},
+};
+
+/// Black wins immediately with c6b6a5 or c6b6a6
+const TEST_SUITE_STR: &str = "# Test suite
+s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2 b 0 1 ; bm c6b6a5 c6b6a6 ; id black win
+
+s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2 b 0 1 ; am c6b6a6 ; score -100 100
+";
+
+#[test]
+fn test_parse_suite() {
+    let entries = parse_suite(TEST_SUITE_STR, &GameOptions::new()).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, Some("black win".to_owned()));
+    assert_eq!(entries[0].best_actions, vec![2827551, 2893087]);
+    assert!(entries[0].avoid_actions.is_empty());
+    assert_eq!(entries[1].id, None);
+    assert_eq!(entries[1].avoid_actions, vec![2893087]);
+    assert_eq!(entries[1].score_range, Some((-100, 100)));
+
+    assert!(entries[0].check(2827551, 0));
+    assert!(entries[0].check(2893087, 0));
+    assert!(!entries[0].check(1975583, 0));
+    assert!(!entries[1].check(2893087, 0));
+    assert!(entries[1].check(1975583, 100));
+    assert!(!entries[1].check(1975583, 101));
+
+    let options = GameOptions::new();
+    assert!(SuiteEntry::parse(
+        "s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2 b 0 1 ; bm",
+        &options
+    )
+    .is_err());
+    assert!(SuiteEntry::parse(
+        "s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp/P-S-R-WWS-PRPS/R-P-S-R-2 b 0 1 ; score 1",
+        &options
+    )
+    .is_err());
+    assert!(SuiteEntry::parse("s-p-r-s-1r-/p-r-s-ww1s-p-/6/7/5rp b 0 1", &options).is_err());
+
+    // Unusual positions are only accepted if they are allowed in the options
+    let unusual_line = "6/7/6/7/6/7/P-P-P-P-P-P- b 0 1 ; id unusual";
+    assert!(SuiteEntry::parse(unusual_line, &options).is_err());
+    let mut options = GameOptions::new();
+    options.allow_unusual_positions = true;
+    assert!(SuiteEntry::parse(unusual_line, &options).is_ok());
+}
+
+#[test]
+fn test_run_suite() {
+    let entries = parse_suite(TEST_SUITE_STR, &GameOptions::new()).unwrap();
+    let mut game = Game::new();
+    game.init();
+    game.play_from_string("a5b5d4").unwrap();
+    let json = serde_json::to_string(&game).unwrap();
+    let results = run_suite(&game, &entries, SuiteLimit::Depth(2), None);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].passed);
+    assert!(!results[1].passed);
+    // The game and its previous positions are unchanged
+    assert_eq!(serde_json::to_string(&game).unwrap(), json);
+}
@@ -0,0 +1,151 @@
+use pijersi_rs::{
+    bitboard::Board,
+    game::Game,
+    search::{alphabeta::SearchMode, SearchResult},
+};
+
+/// Cells state for testing
+///  s- p- r- s- .  r-
+/// p- r- s- ww .  s- p-
+///  .  .  .  .  .  .
+/// .  .  .  .  .  pr .
+///  .  .  .  .  .  .
+/// P- S- R- WW S- R- SP
+///  R- P- S- R- P- .
+const TEST_BOARD_STR: &str =
+    "s-p-r-s-..r-p-r-s-ww..s-p-......................pr..............P-S-R-WWS-R-SPR-P-S-R-P-..";
+
+fn to_binary<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap()
+}
+
+fn from_binary<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+) -> Result<T, bincode::error::DecodeError> {
+    bincode::serde::decode_from_slice(bytes, bincode::config::standard()).map(|(value, _)| value)
+}
+
+#[test]
+fn test_board_serde() {
+    let board = Board::try_from(TEST_BOARD_STR).unwrap();
+
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(json, format!("\"{}\"", board.to_fen()));
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    assert!(serde_json::from_str::<Board>("\"6/7/6\"").is_err());
+
+    let bytes = to_binary(&board);
+    assert_eq!(from_binary::<Board>(&bytes).unwrap(), board);
+    assert!(from_binary::<Board>(&to_binary(&[u64::MAX; 16])).is_err());
+    // Two pieces in the same layer of a cell
+    let mut bitboards = [0u64; 16];
+    bitboards[0] = 1;
+    bitboards[5] = 1;
+    assert!(from_binary::<Board>(&to_binary(&bitboards)).is_err());
+    // A bottom piece without a top piece
+    let mut bitboards = [0u64; 16];
+    bitboards[8] = 1;
+    assert!(from_binary::<Board>(&to_binary(&bitboards)).is_err());
+    bitboards[0] = 1;
+    assert!(from_binary::<Board>(&to_binary(&bitboards)).is_ok());
+}
+
+#[test]
+fn test_game_serde() {
+    let mut game = Game::new();
+    game.init();
+    game.options.use_lmr = false;
+    game.options.search_mode = SearchMode::LazySmp;
+    game.options.contempt = -25;
+    game.options.eval_weights.mobility = 3;
+    for action_string in ["a5b5d4", "g4f5d4", "b4c4"] {
+        game.play_from_string(action_string).unwrap();
+    }
+
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(json.contains("\"search_mode\":\"lazy-smp\""));
+    let json_game: Game = serde_json::from_str(&json).unwrap();
+    let binary_game: Game = from_binary(&to_binary(&game)).unwrap();
+    for new_game in [json_game, binary_game] {
+        assert_eq!(new_game.get_string_state(), game.get_string_state());
+        assert!(!new_game.options.use_lmr);
+        assert_eq!(new_game.options.search_mode, SearchMode::LazySmp);
+        assert_eq!(new_game.options.contempt, -25);
+        assert_eq!(new_game.options.eval_weights, game.options.eval_weights);
+        // The serialized form contains the previous positions
+        assert_eq!(serde_json::to_string(&new_game).unwrap(), json);
+    }
+
+    // The options default to their default values
+    let json = json.replace("\"use_lmr\":false,", "");
+    assert!(serde_json::from_str::<Game>(&json).unwrap().options.use_lmr);
+    let json = json.replace("\"current_player\":1", "\"current_player\":2");
+    assert!(serde_json::from_str::<Game>(&json).is_err());
+}
+
+#[test]
+fn test_game_serde_validation() {
+    let mut game = Game::new();
+    game.init();
+    let start_fen = game.board.to_fen();
+    // Two actions without capture are played
+    for _ in 0..2 {
+        let action = game
+            .board
+            .available_player_actions(game.current_player)
+            .into_iter()
+            .find(|&action| {
+                let mut new_board = game.board;
+                new_board.play_action(action);
+                new_board.count_pieces() == game.board.count_pieces()
+            })
+            .unwrap();
+        game.play(action).unwrap();
+    }
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(json.contains(&format!("\"previous_boards\":[\"{start_fen}\"")));
+
+    // The previous boards must lead to the current board with legal actions
+    let unrelated_fen = Board::try_from(TEST_BOARD_STR).unwrap().to_fen();
+    let tampered_json = json.replacen(&start_fen, &unrelated_fen, 1);
+    assert!(serde_json::from_str::<Game>(&tampered_json).is_err());
+    // There cannot be more previous boards than half moves
+    let tampered_json = json.replace("\"half_moves\":2", "\"half_moves\":1");
+    assert!(serde_json::from_str::<Game>(&tampered_json).is_err());
+
+    // The board is validated unless unusual positions are allowed
+    let mut game = Game::new();
+    game.options.allow_unusual_positions = true;
+    game.set_string_state("6/7/6/7/6/7/P-P-P-P-P-P- w 0 1")
+        .unwrap();
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(serde_json::from_str::<Game>(&json).is_ok());
+    let json = json.replace(
+        "\"allow_unusual_positions\":true",
+        "\"allow_unusual_positions\":false",
+    );
+    assert!(serde_json::from_str::<Game>(&json).is_err());
+}
+
+#[test]
+fn test_search_result_serde() {
+    let mut game = Game::new();
+    game.init();
+    game.options.deterministic = true;
+    let (action, score) = game.search_to_depth(2, None, None).unwrap();
+    let result = SearchResult {
+        action: action.into(),
+        score,
+    };
+
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(
+        json,
+        format!("{{\"action\":\"{}\",\"score\":{score}}}", result.action)
+    );
+    assert_eq!(serde_json::from_str::<SearchResult>(&json).unwrap(), result);
+    assert_eq!(
+        from_binary::<SearchResult>(&to_binary(&result)).unwrap(),
+        result
+    );
+}